@@ -182,6 +182,54 @@ impl<'a, 'gcx, 'tcx> At<'a, 'gcx, 'tcx> {
         self.trace(expected, actual).glb(&expected, &actual)
     }
 
+    /// Computes the greatest-lower-bound of all of `tys` (e.g., the arms
+    /// of a `match`). Rather than folding `glb` over the list, which
+    /// creates a fresh bound (and fresh region constraints) for every
+    /// pair, this creates a single fresh type variable and requires it
+    /// to be a subtype of each type in turn. Types equal to an earlier
+    /// one are skipped. Everything happens in one snapshot, so either
+    /// the whole computation succeeds or it has no effect.
+    ///
+    /// Returns `TypeError::Mismatch` if `tys` is empty, since there is
+    /// nothing to take the bound of.
+    pub fn glb_n(self, tys: &[Ty<'tcx>]) -> InferResult<'tcx, Ty<'tcx>> {
+        let At { infcx, cause, param_env } = self;
+        match tys {
+            [] => return Err(TypeError::Mismatch),
+            [ty] => return Ok(InferOk { value: *ty, obligations: vec![] }),
+            _ => {}
+        }
+        infcx.commit_if_ok(|_| {
+            let glb = infcx.next_ty_var(TypeVariableOrigin {
+                kind: TypeVariableOriginKind::LatticeVariable,
+                span: cause.span,
+            });
+            let mut obligations = vec![];
+            for (i, &ty) in tys.iter().enumerate() {
+                if tys[..i].contains(&ty) {
+                    continue;
+                }
+                let InferOk { obligations: new_obligations, .. } =
+                    infcx.at(cause, param_env).sub(glb, ty)?;
+                obligations.extend(new_obligations);
+            }
+            Ok(InferOk { value: infcx.resolve_vars_if_possible(&glb), obligations })
+        })
+    }
+
+    /// Computes both the greatest-lower-bound and the
+    /// least-upper-bound of two values, as `(glb, lub)`. Both are
+    /// computed in the same snapshot, so either both succeed or
+    /// neither has any effect.
+    pub fn glb_lub<T>(self,
+                      expected: T,
+                      actual: T)
+                      -> InferResult<'tcx, (T, T)>
+        where T: ToTrace<'tcx>
+    {
+        self.trace(expected, actual).glb_lub(&expected, &actual)
+    }
+
     /// Sets the "trace" values that will be used for
     /// error-reporting, but doesn't actually perform any operation
     /// yet (this is useful when you want to set the trace using
@@ -279,6 +327,22 @@ impl<'a, 'gcx, 'tcx> Trace<'a, 'gcx, 'tcx> {
                   .map(move |t| InferOk { value: t, obligations: fields.obligations })
         })
     }
+
+    pub fn glb_lub<T>(self,
+                      a: &T,
+                      b: &T)
+                      -> InferResult<'tcx, (T, T)>
+        where T: Relate<'tcx>
+    {
+        debug!("glb_lub({:?}, {:?})", a, b);
+        let Trace { at, trace, a_is_expected } = self;
+        at.infcx.commit_if_ok(|_| {
+            let mut fields = at.infcx.combine_fields(trace, at.param_env);
+            let glb = fields.glb(a_is_expected).relate(a, b)?;
+            let lub = fields.lub(a_is_expected).relate(a, b)?;
+            Ok(InferOk { value: (glb, lub), obligations: fields.obligations })
+        })
+    }
 }
 
 impl<'tcx> ToTrace<'tcx> for Ty<'tcx> {
@@ -1493,8 +1493,8 @@ impl<'a, 'gcx, 'tcx> InferCtxt<'a, 'gcx, 'tcx> {
             "...",
         );
 
-        match (&sup_origin, &sub_origin) {
-            (&infer::Subtype(ref sup_trace), &infer::Subtype(ref sub_trace)) => {
+        match (sup_origin.type_trace(), sub_origin.type_trace()) {
+            (Some(sup_trace), Some(sub_trace)) => {
                 debug!("report_sub_sup_conflict: var_origin={:?}", var_origin);
                 debug!("report_sub_sup_conflict: sub_region={:?}", sub_region);
                 debug!("report_sub_sup_conflict: sub_origin={:?}", sub_origin);
@@ -1523,6 +1523,16 @@ impl<'a, 'gcx, 'tcx> InferCtxt<'a, 'gcx, 'tcx> {
                             sup_expected.content(),
                             sup_found.content()
                         ));
+                        match (&sup_origin, &sub_origin) {
+                            (&infer::LatticeOp(ref trace), _)
+                            | (_, &infer::LatticeOp(ref trace)) => {
+                                err.note(&format!(
+                                    "...which arose while computing a common type for {}",
+                                    trace.cause.as_lattice_op_str()
+                                ));
+                            }
+                            _ => {}
+                        }
                         err.emit();
                         return;
                     }
@@ -1657,4 +1667,18 @@ impl<'tcx> ObligationCause<'tcx> {
             _ => "types are compatible",
         }
     }
+
+    /// Describes the values whose common type is being computed when a
+    /// lattice operation (GLB/LUB) is performed on behalf of this cause.
+    fn as_lattice_op_str(&self) -> &'static str {
+        use crate::traits::ObligationCauseCode::*;
+        match self.code {
+            MatchExpressionArm { source, .. } => match source {
+                hir::MatchSource::IfLetDesugar { .. } => "the `if let` arms",
+                _ => "two match arms",
+            },
+            IfExpression { .. } => "the `if` and `else` branches",
+            _ => "two types",
+        }
+    }
 }
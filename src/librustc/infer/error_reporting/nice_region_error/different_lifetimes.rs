@@ -2,7 +2,9 @@
 //! where both the regions are anonymous.
 
 use crate::infer::error_reporting::nice_region_error::NiceRegionError;
-use crate::infer::error_reporting::nice_region_error::util::AnonymousArgInfo;
+use crate::infer::error_reporting::nice_region_error::util::{
+    describe_parameter, AnonymousArgInfo,
+};
 use crate::util::common::ErrorReported;
 
 impl<'a, 'gcx, 'tcx> NiceRegionError<'a, 'gcx, 'tcx> {
@@ -71,8 +73,8 @@ impl<'a, 'gcx, 'tcx> NiceRegionError<'a, 'gcx, 'tcx> {
             bregion_sub
         );
 
-        let (ty_sup, ty_fndecl_sup) = ty_sup;
-        let (ty_sub, ty_fndecl_sub) = ty_sub;
+        let (ty_sup, ty_fndecl_sup, index_sup) = ty_sup;
+        let (ty_sub, ty_fndecl_sub, index_sub) = ty_sub;
 
         let AnonymousArgInfo {
             arg: anon_arg_sup, ..
@@ -88,12 +90,12 @@ impl<'a, 'gcx, 'tcx> NiceRegionError<'a, 'gcx, 'tcx> {
 
         let span_label_var1 = match anon_arg_sup.pat.simple_ident() {
             Some(simple_ident) => format!(" from `{}`", simple_ident),
-            None => String::new(),
+            None => format!(" from {}", describe_parameter(index_sup)),
         };
 
         let span_label_var2 = match anon_arg_sub.pat.simple_ident() {
             Some(simple_ident) => format!(" into `{}`", simple_ident),
-            None => String::new(),
+            None => format!(" into {}", describe_parameter(index_sub)),
         };
 
         let (span_1, span_2, main_label, span_label) = match (sup_is_ret_type, sub_is_ret_type) {
@@ -20,12 +20,13 @@ impl<'a, 'gcx, 'tcx> NiceRegionError<'a, 'gcx, 'tcx> {
     ///    { x.push(y); }
     /// ```
     /// The function returns the nested type corresponding to the anonymous region
-    /// for e.g., `&u8` and Vec<`&u8`.
+    /// for e.g., `&u8` and Vec<`&u8`, together with the function declaration and
+    /// the index in `fndecl.inputs` of the parameter containing that type.
     pub(super) fn find_anon_type(
         &self,
         region: Region<'tcx>,
         br: &ty::BoundRegion,
-    ) -> Option<(&hir::Ty, &hir::FnDecl)> {
+    ) -> Option<(&hir::Ty, &hir::FnDecl, usize)> {
         if let Some(anon_reg) = self.tcx().is_suitable_region(region) {
             let def_id = anon_reg.def_id;
             if let Some(node_id) = self.tcx().hir().as_local_node_id(def_id) {
@@ -48,9 +49,12 @@ impl<'a, 'gcx, 'tcx> NiceRegionError<'a, 'gcx, 'tcx> {
                 return fndecl
                     .inputs
                     .iter()
-                    .filter_map(|arg| self.find_component_for_bound_region(arg, br))
+                    .enumerate()
+                    .filter_map(|(index, arg)| {
+                        self.find_component_for_bound_region(arg, br).map(|ty| (ty, index))
+                    })
                     .next()
-                    .map(|ty| (ty, &**fndecl));
+                    .map(|(ty, index)| (ty, &**fndecl, index));
             }
         }
         None
@@ -1,6 +1,7 @@
 //! Error Reporting for Anonymous Region Lifetime Errors
 //! where one region is named and the other is anonymous.
 use crate::infer::error_reporting::nice_region_error::NiceRegionError;
+use crate::infer::error_reporting::nice_region_error::util::describe_parameter;
 use crate::hir::{FunctionRetTy, TyKind};
 use crate::ty;
 use errors::{Applicability, DiagnosticBuilder};
@@ -80,7 +81,8 @@ impl<'a, 'gcx, 'tcx> NiceRegionError<'a, 'gcx, 'tcx> {
             return None;
         }
 
-        if let Some((_, fndecl)) = self.find_anon_type(anon, &br) {
+        let mut anon_index = None;
+        if let Some((_, fndecl, index)) = self.find_anon_type(anon, &br) {
             if self.is_return_type_anon(scope_def_id, br, fndecl).is_some()
                 || self.is_self_anon(is_first, scope_def_id)
             {
@@ -93,6 +95,7 @@ impl<'a, 'gcx, 'tcx> NiceRegionError<'a, 'gcx, 'tcx> {
                     return None;
                 }
             }
+            anon_index = Some(index);
         }
 
         let (error_var, span_label_var) = match arg.pat.simple_ident() {
@@ -100,7 +103,12 @@ impl<'a, 'gcx, 'tcx> NiceRegionError<'a, 'gcx, 'tcx> {
                 format!("the type of `{}`", simple_ident),
                 format!("the type of `{}`", simple_ident),
             ),
-            None => ("parameter type".to_owned(), "type".to_owned()),
+            None => match anon_index {
+                Some(index) => {
+                    (format!("the type of {}", describe_parameter(index)), "type".to_owned())
+                }
+                None => ("parameter type".to_owned(), "type".to_owned()),
+            },
         };
 
         let mut diag = struct_span_err!(
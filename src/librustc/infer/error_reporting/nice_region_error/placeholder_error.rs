@@ -26,7 +26,9 @@ impl NiceRegionError<'me, 'gcx, 'tcx> {
 
             ///////////////////////////////////////////////////////////////////////////
             // Check for errors from comparing trait failures -- first
-            // with two placeholders, then with one.
+            // with two placeholders, then with one. Constraints from GLB/LUB
+            // computations carry a `LatticeOp` origin but are otherwise
+            // reported just like those from plain subtyping.
             Some(RegionResolutionError::SubSupConflict(
                 vid,
                 _,
@@ -37,6 +39,17 @@ impl NiceRegionError<'me, 'gcx, 'tcx> {
                 sub_placeholder @ ty::RePlaceholder(_),
                 _,
                 sup_placeholder @ ty::RePlaceholder(_),
+            ))
+            | Some(RegionResolutionError::SubSupConflict(
+                vid,
+                _,
+                SubregionOrigin::LatticeOp(TypeTrace {
+                    cause,
+                    values: ValuePairs::TraitRefs(ExpectedFound { expected, found }),
+                }),
+                sub_placeholder @ ty::RePlaceholder(_),
+                _,
+                sup_placeholder @ ty::RePlaceholder(_),
             )) if expected.def_id == found.def_id => Some(self.try_report_placeholders_trait(
                 Some(self.tcx().mk_region(ty::ReVar(*vid))),
                 cause,
@@ -57,6 +70,17 @@ impl NiceRegionError<'me, 'gcx, 'tcx> {
                 sub_placeholder @ ty::RePlaceholder(_),
                 _,
                 _,
+            ))
+            | Some(RegionResolutionError::SubSupConflict(
+                vid,
+                _,
+                SubregionOrigin::LatticeOp(TypeTrace {
+                    cause,
+                    values: ValuePairs::TraitRefs(ExpectedFound { expected, found }),
+                }),
+                sub_placeholder @ ty::RePlaceholder(_),
+                _,
+                _,
             )) if expected.def_id == found.def_id => Some(self.try_report_placeholders_trait(
                 Some(self.tcx().mk_region(ty::ReVar(*vid))),
                 cause,
@@ -77,6 +101,17 @@ impl NiceRegionError<'me, 'gcx, 'tcx> {
                 _,
                 _,
                 sup_placeholder @ ty::RePlaceholder(_),
+            ))
+            | Some(RegionResolutionError::SubSupConflict(
+                vid,
+                _,
+                SubregionOrigin::LatticeOp(TypeTrace {
+                    cause,
+                    values: ValuePairs::TraitRefs(ExpectedFound { expected, found }),
+                }),
+                _,
+                _,
+                sup_placeholder @ ty::RePlaceholder(_),
             )) if expected.def_id == found.def_id => Some(self.try_report_placeholders_trait(
                 Some(self.tcx().mk_region(ty::ReVar(*vid))),
                 cause,
@@ -97,6 +132,17 @@ impl NiceRegionError<'me, 'gcx, 'tcx> {
                     values: ValuePairs::TraitRefs(ExpectedFound { expected, found }),
                 }),
                 sup_placeholder @ ty::RePlaceholder(_),
+            ))
+            | Some(RegionResolutionError::SubSupConflict(
+                vid,
+                _,
+                _,
+                _,
+                SubregionOrigin::LatticeOp(TypeTrace {
+                    cause,
+                    values: ValuePairs::TraitRefs(ExpectedFound { expected, found }),
+                }),
+                sup_placeholder @ ty::RePlaceholder(_),
             )) if expected.def_id == found.def_id => Some(self.try_report_placeholders_trait(
                 Some(self.tcx().mk_region(ty::ReVar(*vid))),
                 cause,
@@ -114,6 +160,14 @@ impl NiceRegionError<'me, 'gcx, 'tcx> {
                 }),
                 sub_region @ ty::RePlaceholder(_),
                 sup_region @ ty::RePlaceholder(_),
+            ))
+            | Some(RegionResolutionError::ConcreteFailure(
+                SubregionOrigin::LatticeOp(TypeTrace {
+                    cause,
+                    values: ValuePairs::TraitRefs(ExpectedFound { expected, found }),
+                }),
+                sub_region @ ty::RePlaceholder(_),
+                sup_region @ ty::RePlaceholder(_),
             )) if expected.def_id == found.def_id => Some(self.try_report_placeholders_trait(
                 None,
                 cause,
@@ -131,6 +185,14 @@ impl NiceRegionError<'me, 'gcx, 'tcx> {
                 }),
                 sub_region @ ty::RePlaceholder(_),
                 sup_region,
+            ))
+            | Some(RegionResolutionError::ConcreteFailure(
+                SubregionOrigin::LatticeOp(TypeTrace {
+                    cause,
+                    values: ValuePairs::TraitRefs(ExpectedFound { expected, found }),
+                }),
+                sub_region @ ty::RePlaceholder(_),
+                sup_region,
             )) if expected.def_id == found.def_id => Some(self.try_report_placeholders_trait(
                 Some(sup_region),
                 cause,
@@ -148,6 +210,14 @@ impl NiceRegionError<'me, 'gcx, 'tcx> {
                 }),
                 sub_region,
                 sup_region @ ty::RePlaceholder(_),
+            ))
+            | Some(RegionResolutionError::ConcreteFailure(
+                SubregionOrigin::LatticeOp(TypeTrace {
+                    cause,
+                    values: ValuePairs::TraitRefs(ExpectedFound { expected, found }),
+                }),
+                sub_region,
+                sup_region @ ty::RePlaceholder(_),
             )) if expected.def_id == found.def_id => Some(self.try_report_placeholders_trait(
                 Some(sub_region),
                 cause,
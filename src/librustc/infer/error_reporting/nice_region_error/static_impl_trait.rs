@@ -1,6 +1,8 @@
 //! Error Reporting for static impl Traits.
 
+use crate::infer::SubregionOrigin;
 use crate::infer::error_reporting::nice_region_error::NiceRegionError;
+use crate::infer::error_reporting::note::OPAQUE_STATIC_DEFAULT_NOTE;
 use crate::infer::lexical_region_resolve::RegionResolutionError;
 use crate::ty::{BoundRegion, FreeRegion, RegionKind};
 use crate::util::common::ErrorReported;
@@ -45,6 +47,9 @@ impl<'a, 'gcx, 'tcx> NiceRegionError<'a, 'gcx, 'tcx> {
                             &format!("...can't outlive {}", lifetime),
                         );
                     }
+                    if let SubregionOrigin::OpaqueTypeStaticDefault(_) = sub_origin {
+                        err.note(OPAQUE_STATIC_DEFAULT_NOTE);
+                    }
 
                     let lifetime_name = match sup_r {
                         RegionKind::ReFree(FreeRegion {
@@ -132,3 +132,18 @@ impl<'a, 'gcx, 'tcx> NiceRegionError<'a, 'gcx, 'tcx> {
     }
 
 }
+
+/// Describes the parameter at position `index` (counting from zero) of a
+/// function declaration, e.g. "the 3rd parameter", for use in diagnostics
+/// when the parameter's pattern has no simple name.
+pub(super) fn describe_parameter(index: usize) -> String {
+    let n = index + 1;
+    let suffix = match (n % 10, n % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+    format!("the {}{} parameter", n, suffix)
+}
@@ -4,6 +4,11 @@ use crate::ty::{self, Region};
 use crate::ty::error::TypeError;
 use errors::DiagnosticBuilder;
 
+/// Explains why the regions in the hidden type of an opaque type were
+/// required to outlive `'static`.
+pub(super) const OPAQUE_STATIC_DEFAULT_NOTE: &str =
+    "no lifetime parameters in the `impl Trait`, so its hidden type must be `'static`";
+
 impl<'a, 'gcx, 'tcx> InferCtxt<'a, 'gcx, 'tcx> {
     pub(super) fn note_region_origin(&self,
                                      err: &mut DiagnosticBuilder<'_>,
@@ -26,6 +31,11 @@ impl<'a, 'gcx, 'tcx> InferCtxt<'a, 'gcx, 'tcx> {
                                   &format!("...so that {}", trace.cause.as_requirement_str()));
                 }
             }
+            infer::LatticeOp(ref trace) => {
+                self.note_region_origin(err, &infer::Subtype(trace.clone()));
+                err.note(&format!("...which arose while computing a common type for {}",
+                                  trace.cause.as_lattice_op_str()));
+            }
             infer::Reborrow(span) => {
                 err.span_note(span,
                               "...so that reference does not outlive borrowed content");
@@ -68,6 +78,10 @@ impl<'a, 'gcx, 'tcx> InferCtxt<'a, 'gcx, 'tcx> {
             infer::CallReturn(span) => {
                 err.span_note(span, "...so that return value is valid for the call");
             }
+            infer::OpaqueTypeStaticDefault(span) => {
+                err.span_note(span, "...so that return value is valid for the call");
+                err.note(OPAQUE_STATIC_DEFAULT_NOTE);
+            }
             infer::Operand(span) => {
                 err.span_note(span, "...so that operand is valid for operation");
             }
@@ -146,6 +160,16 @@ impl<'a, 'gcx, 'tcx> InferCtxt<'a, 'gcx, 'tcx> {
                     "...does not necessarily outlive ", sub, "");
                 err
             }
+            infer::LatticeOp(trace) => {
+                let note = format!("this requirement arose while computing a common type for {}",
+                                   trace.cause.as_lattice_op_str());
+                let mut err = self.report_concrete_failure(region_scope_tree,
+                                                           infer::Subtype(trace),
+                                                           sub,
+                                                           sup);
+                err.note(&note);
+                err
+            }
             infer::Reborrow(span) => {
                 let mut err = struct_span_err!(self.tcx.sess,
                                                span,
@@ -327,6 +351,19 @@ impl<'a, 'gcx, 'tcx> InferCtxt<'a, 'gcx, 'tcx> {
                                                  "");
                 err
             }
+            infer::OpaqueTypeStaticDefault(span) => {
+                let mut err = struct_span_err!(self.tcx.sess,
+                                               span,
+                                               E0482,
+                                               "lifetime of return value does not outlive the \
+                                                function call");
+                self.tcx.note_and_explain_region(region_scope_tree, &mut err,
+                                                 "the return value is only valid for ",
+                                                 sup,
+                                                 "");
+                err.note(OPAQUE_STATIC_DEFAULT_NOTE);
+                err
+            }
             infer::Operand(span) => {
                 let mut err = struct_span_err!(self.tcx.sess,
                                                span,
@@ -450,7 +487,7 @@ impl<'a, 'gcx, 'tcx> InferCtxt<'a, 'gcx, 'tcx> {
     ) -> DiagnosticBuilder<'tcx> {
         // I can't think how to do better than this right now. -nikomatsakis
         match placeholder_origin {
-            infer::Subtype(trace) => {
+            infer::Subtype(trace) | infer::LatticeOp(trace) => {
                 let terr = TypeError::RegionsPlaceholderMismatch;
                 self.report_and_explain_type_error(trace, &terr)
             }
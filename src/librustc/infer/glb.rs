@@ -1,7 +1,7 @@
 use super::combine::CombineFields;
 use super::InferCtxt;
 use super::lattice::{self, LatticeDir};
-use super::Subtype;
+use super::LatticeOp;
 
 use crate::traits::ObligationCause;
 use crate::ty::{self, Ty, TyCtxt};
@@ -44,7 +44,10 @@ impl TypeRelation<'gcx, 'tcx> for Glb<'combine, 'infcx, 'gcx, 'tcx> {
     }
 
     fn tys(&mut self, a: Ty<'tcx>, b: Ty<'tcx>) -> RelateResult<'tcx, Ty<'tcx>> {
-        lattice::super_lattice_tys(self, a, b)
+        let glb = lattice::super_lattice_tys(self, a, b)?;
+        #[cfg(debug_assertions)]
+        self.check_glb(glb, a, b);
+        Ok(glb)
     }
 
     fn regions(&mut self, a: ty::Region<'tcx>, b: ty::Region<'tcx>)
@@ -54,7 +57,7 @@ impl TypeRelation<'gcx, 'tcx> for Glb<'combine, 'infcx, 'gcx, 'tcx> {
                a,
                b);
 
-        let origin = Subtype(self.fields.trace.clone());
+        let origin = LatticeOp(self.fields.trace.clone());
         Ok(self.fields.infcx.borrow_region_constraints().glb_regions(self.tcx(), origin, a, b))
     }
 
@@ -103,3 +106,24 @@ impl<'combine, 'infcx, 'gcx, 'tcx> LatticeDir<'infcx, 'gcx, 'tcx>
         Ok(())
     }
 }
+
+impl<'combine, 'infcx, 'gcx, 'tcx> Glb<'combine, 'infcx, 'gcx, 'tcx> {
+    /// Checks, without affecting the inference state, that `glb`, the GLB
+    /// computed for `a` and `b`, is indeed a subtype of both of them. The
+    /// check relates the whole of `glb` to the original inputs with `Sub`,
+    /// independently of the structural walk that computed it.
+    #[cfg(debug_assertions)]
+    fn check_glb(&mut self, glb: Ty<'tcx>, a: Ty<'tcx>, b: Ty<'tcx>) {
+        let infcx = self.fields.infcx;
+        let obligations_len = self.fields.obligations.len();
+        let is_bound = infcx.probe(|_| {
+            let mut sub = self.fields.sub(self.a_is_expected);
+            sub.relate(&glb, &a).is_ok() && sub.relate(&glb, &b).is_ok()
+        });
+        self.fields.obligations.truncate(obligations_len);
+
+        if !is_bound {
+            bug!("Glb::tys: {:?} is not a subtype of both {:?} and {:?}", glb, a, b);
+        }
+    }
+}
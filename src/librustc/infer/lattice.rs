@@ -95,6 +95,9 @@ pub fn super_lattice_tys<'a, 'gcx, 'tcx, L>(this: &mut L,
             Ok(v)
         }
 
+        // This also covers integer and float variables: two of them are
+        // unified, and one of them and a concrete integer (float) type
+        // results in that type, e.g., `i64` for `0` and `1i64`.
         _ => {
             infcx.super_combine_tys(this, a, b)
         }
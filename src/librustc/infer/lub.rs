@@ -1,7 +1,7 @@
 use super::combine::CombineFields;
 use super::InferCtxt;
 use super::lattice::{self, LatticeDir};
-use super::Subtype;
+use super::LatticeOp;
 
 use crate::traits::ObligationCause;
 use crate::ty::{self, Ty, TyCtxt};
@@ -54,7 +54,7 @@ impl TypeRelation<'gcx, 'tcx> for Lub<'combine, 'infcx, 'gcx, 'tcx> {
                a,
                b);
 
-        let origin = Subtype(self.fields.trace.clone());
+        let origin = LatticeOp(self.fields.trace.clone());
         Ok(self.fields.infcx.borrow_region_constraints().lub_regions(self.tcx(), origin, a, b))
     }
 
@@ -152,6 +152,12 @@ pub struct InferCtxt<'a, 'gcx, 'tcx> {
     /// avoid reporting the same error twice.
     pub reported_trait_errors: RefCell<FxHashMap<Span, Vec<ty::Predicate<'tcx>>>>,
 
+    /// Caches `required_region_bounds` for each instantiation of an
+    /// opaque type, which is needed both when the opaque type is
+    /// instantiated and when it is constrained. The substs may refer
+    /// to inference variables, so this is cleared on snapshot rollback.
+    opaque_region_bounds_cache: RefCell<FxHashMap<(DefId, SubstsRef<'tcx>), Vec<ty::Region<'tcx>>>>,
+
     /// When an error occurs, we want to avoid reporting "derived"
     /// errors that are due to this original failure. Normally, we
     /// handle this with the `err_count_on_creation` count, which
@@ -254,6 +260,10 @@ pub enum SubregionOrigin<'tcx> {
     /// Arose from a subtyping relation
     Subtype(TypeTrace<'tcx>),
 
+    /// Arose from computing the greatest lower bound or least upper
+    /// bound of two types, e.g., the common type of two match arms
+    LatticeOp(TypeTrace<'tcx>),
+
     /// Stack-allocated closures cannot outlive innermost loop
     /// or function so as to ensure we only require finite stack
     InfStackClosure(Span),
@@ -316,6 +326,10 @@ pub enum SubregionOrigin<'tcx> {
     /// Region in return type of invoked fn must enclose call
     CallReturn(Span),
 
+    /// Regions in the hidden type of an opaque type without lifetime
+    /// parameters, which must therefore outlive `'static`
+    OpaqueTypeStaticDefault(Span),
+
     /// Operands must be in scope
     Operand(Span),
 
@@ -530,6 +544,7 @@ impl<'gcx, 'tcx> InferCtxtBuilder<'gcx, 'tcx> {
                 selection_cache: Default::default(),
                 evaluation_cache: Default::default(),
                 reported_trait_errors: Default::default(),
+                opaque_region_bounds_cache: Default::default(),
                 tainted_by_errors_flag: Cell::new(false),
                 err_count_on_creation: tcx.sess.err_count(),
                 in_snapshot: Cell::new(false),
@@ -603,6 +618,12 @@ impl<'a, 'gcx, 'tcx> InferCtxt<'a, 'gcx, 'tcx> {
         self.in_snapshot.get()
     }
 
+    /// Returns whether the required region bounds of the opaque type `def_id`
+    /// instantiated with `substs` are cached, i.e., would not be recomputed.
+    pub fn opaque_region_bounds_cached(&self, def_id: DefId, substs: SubstsRef<'tcx>) -> bool {
+        self.opaque_region_bounds_cache.borrow().contains_key(&(def_id, substs))
+    }
+
     pub fn freshen<T: TypeFoldable<'tcx>>(&self, t: T) -> T {
         t.fold_with(&mut self.freshener())
     }
@@ -761,6 +782,7 @@ impl<'a, 'gcx, 'tcx> InferCtxt<'a, 'gcx, 'tcx> {
         self.float_unification_table.borrow_mut().rollback_to(float_snapshot);
         self.region_obligations.borrow_mut().truncate(region_obligations_snapshot);
         self.borrow_region_constraints().rollback_to(region_constraints_snapshot);
+        self.opaque_region_bounds_cache.borrow_mut().clear();
     }
 
     fn commit_from(&self, snapshot: CombinedSnapshot<'a, 'tcx>) {
@@ -877,6 +899,24 @@ impl<'a, 'gcx, 'tcx> InferCtxt<'a, 'gcx, 'tcx> {
         })
     }
 
+    /// Computes the greatest-lower-bound of `a` and `b`, returning it along
+    /// with the obligations that must hold for it. If that fails, all side
+    /// effects of the attempt (including region constraints) are rolled
+    /// back and `None` is returned.
+    pub fn try_glb(
+        &self,
+        cause: &ObligationCause<'tcx>,
+        param_env: ty::ParamEnv<'tcx>,
+        a: Ty<'tcx>,
+        b: Ty<'tcx>,
+    ) -> Option<(Ty<'tcx>, PredicateObligations<'tcx>)> {
+        // `glb` runs in `commit_if_ok`, so an error leaves no trace.
+        self.at(cause, param_env)
+            .glb(a, b)
+            .ok()
+            .map(|InferOk { value, obligations }| (value, obligations))
+    }
+
     pub fn can_eq<T>(&self, param_env: ty::ParamEnv<'tcx>, a: T, b: T) -> UnitResult<'tcx>
     where
         T: at::ToTrace<'tcx>,
@@ -1662,6 +1702,7 @@ impl<'tcx> SubregionOrigin<'tcx> {
     pub fn span(&self) -> Span {
         match *self {
             Subtype(ref a) => a.span(),
+            LatticeOp(ref a) => a.span(),
             InfStackClosure(a) => a,
             InvokeClosure(a) => a,
             DerefPointer(a) => a,
@@ -1681,6 +1722,7 @@ impl<'tcx> SubregionOrigin<'tcx> {
             CallRcvr(a) => a,
             CallArg(a) => a,
             CallReturn(a) => a,
+            OpaqueTypeStaticDefault(a) => a,
             Operand(a) => a,
             AddrOf(a) => a,
             AutoBorrow(a) => a,
@@ -1689,6 +1731,15 @@ impl<'tcx> SubregionOrigin<'tcx> {
         }
     }
 
+    /// The type trace of a constraint that arose from relating two types,
+    /// either by plain subtyping or while computing a GLB/LUB.
+    pub fn type_trace(&self) -> Option<&TypeTrace<'tcx>> {
+        match *self {
+            Subtype(ref trace) | LatticeOp(ref trace) => Some(trace),
+            _ => None,
+        }
+    }
+
     pub fn from_obligation_cause<F>(cause: &traits::ObligationCause<'tcx>, default: F) -> Self
     where
         F: FnOnce() -> Self,
@@ -1,9 +1,13 @@
-use rustc_data_structures::fx::FxHashMap;
-use syntax_pos::Span;
+use errors::{Applicability, DiagnosticBuilder};
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+use rustc_macros::HashStable;
+use syntax::symbol::kw;
+use syntax_pos::{BytePos, Span};
 
 use crate::hir::def_id::DefId;
 use crate::hir;
 use crate::hir::Node;
+use crate::hir::intravisit::{self, NestedVisitorMap, Visitor};
 use crate::infer::{self, InferCtxt, InferOk, TypeVariableOrigin, TypeVariableOriginKind};
 use crate::infer::outlives::free_region_map::FreeRegionRelations;
 use crate::traits::{self, PredicateObligation};
@@ -17,7 +21,7 @@ pub type OpaqueTypeMap<'tcx> = DefIdMap<OpaqueTypeDecl<'tcx>>;
 /// Information about the opaque, abstract types whose values we
 /// are inferring in this function (these are the `impl Trait` that
 /// appear in the return type).
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, HashStable)]
 pub struct OpaqueTypeDecl<'tcx> {
     /// The substitutions that we apply to the abstract that this
     /// `impl Trait` desugars to. e.g., if:
@@ -69,6 +73,11 @@ pub struct OpaqueTypeDecl<'tcx> {
     /// check.)
     pub has_required_region_bounds: bool,
 
+    /// The number of lifetime parameters of the abstract type, i.e., the
+    /// number of region arguments in `substs`. `constrain_opaque_type` uses
+    /// this to avoid looking at the generics when there are none.
+    pub lifetime_param_count: usize,
+
     /// The origin of the existential type
     pub origin: hir::ExistTyOrigin,
 }
@@ -116,6 +125,8 @@ impl<'a, 'gcx, 'tcx> InferCtxt<'a, 'gcx, 'tcx> {
             param_env,
             opaque_types: Default::default(),
             obligations: vec![],
+            nesting_depth: 0,
+            outermost_opaque: None,
         };
         let value = instantiator.instantiate_opaque_types_in_map(value);
         InferOk {
@@ -124,6 +135,75 @@ impl<'a, 'gcx, 'tcx> InferCtxt<'a, 'gcx, 'tcx> {
         }
     }
 
+    /// Like `instantiate_opaque_types`, but for the predicate of a single
+    /// obligation, using the obligation's own body-id and parameter
+    /// environment. Returns the rewritten obligation.
+    pub fn instantiate_opaque_types_in_obligation(
+        &self,
+        parent_def_id: DefId,
+        obligation: &PredicateObligation<'tcx>,
+    ) -> InferOk<'tcx, (PredicateObligation<'tcx>, OpaqueTypeMap<'tcx>)> {
+        debug!(
+            "instantiate_opaque_types_in_obligation(obligation={:?}, parent_def_id={:?})",
+            obligation, parent_def_id,
+        );
+        let InferOk { value: (predicate, opaque_types), obligations } = self
+            .instantiate_opaque_types(
+                parent_def_id,
+                obligation.cause.body_id,
+                obligation.param_env,
+                &obligation.predicate,
+            );
+        let obligation = traits::Obligation { predicate, ..obligation.clone() };
+        InferOk { value: (obligation, opaque_types), obligations }
+    }
+
+    /// Merges the opaque types instantiated in `other` into `opaque_types`.
+    /// An opaque type that appears in both maps must have been
+    /// instantiated with the same substitutions, and the two inference
+    /// variables for its hidden type are equated. If that is not possible,
+    /// the `DefId` of the conflicting opaque type is returned and
+    /// `opaque_types` is left unchanged; otherwise, the obligations from
+    /// equating the hidden types are returned.
+    pub fn merge_opaque_type_maps(
+        &self,
+        cause: &traits::ObligationCause<'tcx>,
+        param_env: ty::ParamEnv<'tcx>,
+        opaque_types: &mut OpaqueTypeMap<'tcx>,
+        other: OpaqueTypeMap<'tcx>,
+    ) -> Result<InferOk<'tcx, ()>, DefId> {
+        debug!("merge_opaque_type_maps(opaque_types={:?}, other={:?})", opaque_types, other);
+
+        // Sort the colliding entries so that the reported conflict, if
+        // any, does not depend on hash map iteration order.
+        let mut collisions: Vec<_> = other
+            .iter()
+            .filter_map(|(def_id, decl)| Some((*def_id, opaque_types.get(def_id)?, decl)))
+            .collect();
+        collisions.sort_by_key(|&(def_id, _, _)| def_id);
+
+        let obligations = self.commit_if_ok(|_| {
+            let mut obligations = vec![];
+            for (def_id, existing, decl) in collisions {
+                if existing.substs != decl.substs {
+                    return Err(def_id);
+                }
+                let InferOk { obligations: eq_obligations, .. } = self
+                    .at(cause, param_env)
+                    .eq(existing.concrete_ty, decl.concrete_ty)
+                    .map_err(|_| def_id)?;
+                obligations.extend(eq_obligations);
+            }
+            Ok(obligations)
+        })?;
+
+        for (def_id, decl) in other {
+            opaque_types.entry(def_id).or_insert(decl);
+        }
+
+        Ok(InferOk { value: (), obligations })
+    }
+
     /// Given the map `opaque_types` containing the existential `impl
     /// Trait` types whose underlying, hidden types are being
     /// inferred, this method adds constraints to the regions
@@ -269,6 +349,11 @@ impl<'a, 'gcx, 'tcx> InferCtxt<'a, 'gcx, 'tcx> {
     ) {
         debug!("constrain_opaque_types()");
 
+        // `OpaqueTypeMap` is a hash map, so sort the entries to get the
+        // errors below emitted in a deterministic order.
+        let mut opaque_types: Vec<_> = opaque_types.iter().collect();
+        opaque_types.sort_by_key(|&(&def_id, _)| def_id);
+
         for (&def_id, opaque_defn) in opaque_types {
             self.constrain_opaque_type(def_id, opaque_defn, free_region_relations);
         }
@@ -290,32 +375,28 @@ impl<'a, 'gcx, 'tcx> InferCtxt<'a, 'gcx, 'tcx> {
 
         debug!("constrain_opaque_type: concrete_ty={:?}", concrete_ty);
 
-        let abstract_type_generics = tcx.generics_of(def_id);
-
         let span = tcx.def_span(def_id);
 
         // If there are required region bounds, we can use them.
         if opaque_defn.has_required_region_bounds {
-            let predicates_of = tcx.predicates_of(def_id);
-            debug!(
-                "constrain_opaque_type: predicates: {:#?}",
-                predicates_of,
-            );
-            let bounds = predicates_of.instantiate(tcx, opaque_defn.substs);
-            debug!("constrain_opaque_type: bounds={:#?}", bounds);
-            let opaque_type = tcx.mk_opaque(def_id, opaque_defn.substs);
+            // If the hidden type has no free regions, there is nothing for
+            // the bounds to constrain, so don't bother computing them. (We
+            // can't return this early in the `least_region` case below, as
+            // that may report ambiguous bounds regardless of the hidden type.)
+            if !concrete_ty.has_free_regions() {
+                debug!("constrain_opaque_type: no free regions in concrete_ty");
+                return;
+            }
 
-            let required_region_bounds = tcx.required_region_bounds(
-                opaque_type,
-                bounds.predicates.clone(),
-            );
+            let required_region_bounds =
+                self.opaque_required_region_bounds(def_id, opaque_defn.substs);
+            debug!("constrain_opaque_type: required_region_bounds={:?}", required_region_bounds);
             debug_assert!(!required_region_bounds.is_empty());
 
             for region in required_region_bounds {
-                concrete_ty.visit_with(&mut OpaqueTypeOutlivesVisitor {
-                    infcx: self,
-                    least_region: region,
-                    span,
+                concrete_ty.visit_with(&mut OpaqueTypeRegionVisitor {
+                    tcx: self.tcx,
+                    op: |r| self.sub_regions(infer::CallReturn(span), region, r),
                 });
             }
             return;
@@ -328,79 +409,217 @@ impl<'a, 'gcx, 'tcx> InferCtxt<'a, 'gcx, 'tcx> {
         // type; so in our example above, `substs` would contain
         // `['a]` for the first impl trait and `'b` for the
         // second.
-        let mut least_region = None;
-        for param in &abstract_type_generics.params {
-            match param.kind {
-                GenericParamDefKind::Lifetime => {}
-                _ => continue
+        let candidate_regions = self.opaque_candidate_regions(def_id, opaque_defn);
+        debug!("constrain_opaque_type: candidate_regions={:?}", candidate_regions);
+
+        let least_region = match find_least_region(&candidate_regions, free_region_relations) {
+            Ok(least_region) => least_region,
+            Err(error) => {
+                // There are two regions which are not relatable. We can't
+                // find a best choice.
+                let msg = format!(
+                    "ambiguous lifetime bound in `{}`",
+                    origin_description(opaque_defn.origin),
+                );
+                let mut err = self.tcx
+                    .sess
+                    .struct_span_err(span, &msg);
+
+                let label = match error {
+                    LeastRegionError::ElidedUnrelated => {
+                        String::from("the elided lifetimes here do not outlive one another")
+                    }
+                    LeastRegionError::Unrelated { a, b } => {
+                        format!("neither `{}` nor `{}` outlives the other", a, b)
+                    }
+                };
+                err.span_label(span, label);
+
+                // The label only names two of the lifetimes; if there are
+                // more, list all of them.
+                let mut candidate_names: Vec<String> = vec![];
+                for region in &candidate_regions {
+                    let name = region.to_string();
+                    if !candidate_names.contains(&name) {
+                        candidate_names.push(name);
+                    }
+                }
+                if candidate_names.len() > 2 {
+                    let candidate_names: Vec<_> = candidate_names
+                        .iter()
+                        .map(|name| format!("`{}`", name))
+                        .collect();
+                    err.note(&format!(
+                        "candidate lifetimes: {}",
+                        candidate_names.join(", "),
+                    ));
+                }
+
+                if let hir::ExistTyOrigin::AsyncFn = opaque_defn.origin {
+                    err.note("multiple unrelated lifetimes are not allowed in \
+                             `async fn`.");
+                    err.note("if you're using argument-position elided lifetimes, consider \
+                        switching to a single named lifetime.");
+                    if let Some(fn_def_id) = opaque_parent_fn(tcx, def_id) {
+                        suggest_single_named_lifetime(tcx, fn_def_id, &mut err);
+                    }
+                }
+                err.emit();
+
+                Some(self.tcx.mk_region(ty::ReEmpty))
             }
-            // Get the value supplied for this region from the substs.
-            let subst_arg = opaque_defn.substs.region_at(param.index as usize);
-
-            // Compute the least upper bound of it with the other regions.
-            debug!("constrain_opaque_types: least_region={:?}", least_region);
-            debug!("constrain_opaque_types: subst_arg={:?}", subst_arg);
-            match least_region {
-                None => least_region = Some(subst_arg),
-                Some(lr) => {
-                    if free_region_relations.sub_free_regions(lr, subst_arg) {
-                        // keep the current least region
-                    } else if free_region_relations.sub_free_regions(subst_arg, lr) {
-                        // switch to `subst_arg`
-                        least_region = Some(subst_arg);
-                    } else {
-                        // There are two regions (`lr` and
-                        // `subst_arg`) which are not relatable. We can't
-                        // find a best choice.
-                        let context_name = match opaque_defn.origin {
-                            hir::ExistTyOrigin::ExistentialType => "existential type",
-                            hir::ExistTyOrigin::ReturnImplTrait => "impl Trait",
-                            hir::ExistTyOrigin::AsyncFn => "async fn",
-                        };
-                        let msg = format!("ambiguous lifetime bound in `{}`", context_name);
-                        let mut err = self.tcx
-                            .sess
-                            .struct_span_err(span, &msg);
-
-                        let lr_name = lr.to_string();
-                        let subst_arg_name = subst_arg.to_string();
-                        let label_owned;
-                        let label = match (&*lr_name, &*subst_arg_name) {
-                            ("'_", "'_") => "the elided lifetimes here do not outlive one another",
-                            _ => {
-                                label_owned = format!(
-                                    "neither `{}` nor `{}` outlives the other",
-                                    lr_name,
-                                    subst_arg_name,
-                                );
-                                &label_owned
-                            }
-                        };
-                        err.span_label(span, label);
+        };
 
-                        if let hir::ExistTyOrigin::AsyncFn = opaque_defn.origin {
-                            err.note("multiple unrelated lifetimes are not allowed in \
-                                     `async fn`.");
-                            err.note("if you're using argument-position elided lifetimes, consider \
-                                switching to a single named lifetime.");
-                        }
-                        err.emit();
+        // If the opaque type has no lifetime parameters at all, its hidden
+        // type may not capture any region but `'static`. Record that in the
+        // origin so region errors can explain where the `'static` came from.
+        let (least_region, origin) = match least_region {
+            Some(lr) => (lr, infer::CallReturn(span)),
+            None => (tcx.lifetimes.re_static, infer::OpaqueTypeStaticDefault(span)),
+        };
+        debug!("constrain_opaque_types: least_region={:?}", least_region);
 
-                        least_region = Some(self.tcx.mk_region(ty::ReEmpty));
-                        break;
-                    }
+        concrete_ty.visit_with(&mut OpaqueTypeRegionVisitor {
+            tcx: self.tcx,
+            op: |r| self.sub_regions(origin.clone(), least_region, r),
+        });
+    }
+
+    /// Returns whether `region`, a free region or `'static`, may appear in the
+    /// hidden type of the opaque type `def_id`, without emitting any errors: it
+    /// has to outlive all of the required region bounds if there are any, and
+    /// otherwise one of the regions the hidden type may capture (so if there is
+    /// none, it has to be `'static`). This is the check `constrain_opaque_type`
+    /// imposes on each region of the hidden type, except that ambiguous
+    /// candidate regions are not an error here.
+    pub fn region_acceptable_for_opaque<FRR: FreeRegionRelations<'tcx>>(
+        &self,
+        def_id: DefId,
+        opaque_defn: &OpaqueTypeDecl<'tcx>,
+        region: ty::Region<'tcx>,
+        free_region_relations: &FRR,
+    ) -> bool {
+        if let ty::ReStatic = region {
+            return true;
+        }
+
+        if opaque_defn.has_required_region_bounds {
+            return self.opaque_required_region_bounds(def_id, opaque_defn.substs)
+                .into_iter()
+                .all(|bound| free_region_relations.sub_free_regions(bound, region));
+        }
+
+        self.opaque_candidate_regions(def_id, opaque_defn)
+            .into_iter()
+            .any(|candidate| free_region_relations.sub_free_regions(candidate, region))
+    }
+
+    /// Returns the regions that the hidden type of the opaque type `def_id` may
+    /// capture if it has no required region bounds: the lifetime arguments in
+    /// its `substs`, followed by the free regions on the right-hand side of
+    /// associated-type equalities in its bounds. An opaque type without
+    /// lifetime parameters cannot name any region but `'static` in its bounds,
+    /// so neither its generics nor its bounds are looked at in that case.
+    fn opaque_candidate_regions(
+        &self,
+        def_id: DefId,
+        opaque_defn: &OpaqueTypeDecl<'tcx>,
+    ) -> Vec<ty::Region<'tcx>> {
+        let tcx = self.tcx;
+
+        if opaque_defn.lifetime_param_count == 0 {
+            return vec![];
+        }
+
+        let mut candidate_regions: Vec<ty::Region<'tcx>> = tcx
+            .generics_of(def_id)
+            .params
+            .iter()
+            .filter_map(|param| match param.kind {
+                // Get the value supplied for this region from the substs.
+                GenericParamDefKind::Lifetime => {
+                    Some(opaque_defn.substs.region_at(param.index as usize))
                 }
+                _ => None,
+            })
+            .collect();
+
+        // Regions can also reach the hidden type through associated-type
+        // equalities in the bounds, e.g., `'a` in `impl Iterator<Item = &'a u8>`,
+        // so consider the regions on the right-hand side of those as well.
+        let bounds = tcx.predicates_of(def_id).instantiate(tcx, opaque_defn.substs);
+        for predicate in &bounds.predicates {
+            if let ty::Predicate::Projection(projection) = predicate {
+                tcx.for_each_free_region(&projection.skip_binder().ty, |r| match r {
+                    // Skipping the binder exposes the regions it binds, like
+                    // the elided lifetimes in `impl Fn(&u8) -> &u8`; these are
+                    // not regions the hidden type can capture. `'static` is
+                    // never the least region unless it is the only one, which
+                    // is already the default.
+                    ty::ReLateBound(..) | ty::ReStatic => {}
+                    _ => {
+                        if !candidate_regions.contains(&r) {
+                            candidate_regions.push(r);
+                        }
+                    }
+                });
             }
         }
 
-        let least_region = least_region.unwrap_or(tcx.lifetimes.re_static);
-        debug!("constrain_opaque_types: least_region={:?}", least_region);
+        candidate_regions
+    }
 
-        concrete_ty.visit_with(&mut OpaqueTypeOutlivesVisitor {
-            infcx: self,
-            least_region,
-            span,
+    /// Returns `tcx.required_region_bounds` for the opaque type `def_id`
+    /// instantiated with `substs`, computing it only once per instantiation.
+    fn opaque_required_region_bounds(
+        &self,
+        def_id: DefId,
+        substs: SubstsRef<'tcx>,
+    ) -> Vec<ty::Region<'tcx>> {
+        if let Some(bounds) = self.opaque_region_bounds_cache.borrow().get(&(def_id, substs)) {
+            debug!("opaque_required_region_bounds({:?}, {:?}): cache hit", def_id, substs);
+            return bounds.clone();
+        }
+
+        let tcx = self.tcx;
+        let opaque_type = tcx.mk_opaque(def_id, substs);
+        let predicates = tcx.predicates_of(def_id).instantiate(tcx, substs).predicates;
+        let bounds = tcx.required_region_bounds(opaque_type, predicates);
+        debug!("opaque_required_region_bounds({:?}, {:?}) = {:?}", def_id, substs, bounds);
+        self.opaque_region_bounds_cache.borrow_mut().insert((def_id, substs), bounds.clone());
+        bounds
+    }
+
+    /// Returns the free regions captured by `concrete_ty`, the hidden type of
+    /// an opaque type, in the order they are first encountered. These are
+    /// exactly the regions that `constrain_opaque_type` requires to outlive
+    /// the opaque type's region bound; in particular, the lifetime parameters
+    /// of the item enclosing a closure or generator are not included.
+    pub fn opaque_captured_regions(&self, concrete_ty: Ty<'tcx>) -> Vec<ty::Region<'tcx>> {
+        let mut regions = vec![];
+        concrete_ty.visit_with(&mut OpaqueTypeRegionVisitor {
+            tcx: self.tcx,
+            op: |r| if !regions.contains(&r) {
+                regions.push(r);
+            },
         });
+        regions
+    }
+
+    /// Replaces every opaque type in `value` that has an entry in `opaque_types`
+    /// with its (resolved) concrete type, e.g., `(impl Debug, impl Display)` with
+    /// `(u32, String)`. Opaque types that would have to be expanded within their own
+    /// expansion (which can only happen for erroneous code) are left as they are.
+    pub fn expand_opaque_types<T: TypeFoldable<'tcx>>(
+        &self,
+        value: &T,
+        opaque_types: &OpaqueTypeMap<'tcx>,
+    ) -> T {
+        value.fold_with(&mut OpaqueTypeExpander {
+            infcx: self,
+            opaque_types,
+            expanding: FxHashSet::default(),
+        })
     }
 
     /// Given the fully resolved, instantiated type for an opaque
@@ -461,6 +680,7 @@ impl<'a, 'gcx, 'tcx> InferCtxt<'a, 'gcx, 'tcx> {
                 self.tcx,
                 self.is_tainted_by_errors(),
                 def_id,
+                opaque_defn.origin,
                 map,
                 instantiated_ty,
             ));
@@ -478,8 +698,10 @@ impl<'a, 'gcx, 'tcx> InferCtxt<'a, 'gcx, 'tcx> {
     }
 }
 
-// Visitor that requires that (almost) all regions in the type visited outlive
-// `least_region`. We cannot use `push_outlives_components` because regions in
+// Visitor that calls `op` on (almost) all regions in the type visited. This is
+// used to require that those regions outlive the least region of an opaque
+// type, and to collect them for diagnostics. We cannot use
+// `push_outlives_components` because regions in
 // closure signatures are not included in their outlives components. We need to
 // ensure all regions outlive the given bound so that we don't end up with,
 // say, `ReScope` appearing in a return type and causing ICEs when other
@@ -491,13 +713,14 @@ impl<'a, 'gcx, 'tcx> InferCtxt<'a, 'gcx, 'tcx> {
 //
 // We ignore any type parameters because impl trait values are assumed to
 // capture all the in-scope type parameters.
-struct OpaqueTypeOutlivesVisitor<'a, 'gcx, 'tcx> {
-    infcx: &'a InferCtxt<'a, 'gcx, 'tcx>,
-    least_region: ty::Region<'tcx>,
-    span: Span,
+struct OpaqueTypeRegionVisitor<'gcx, 'tcx, OP> {
+    tcx: TyCtxt<'gcx, 'tcx>,
+    op: OP,
 }
 
-impl<'tcx> TypeVisitor<'tcx> for OpaqueTypeOutlivesVisitor<'_, '_, 'tcx>
+impl<'tcx, OP> TypeVisitor<'tcx> for OpaqueTypeRegionVisitor<'_, 'tcx, OP>
+where
+    OP: FnMut(ty::Region<'tcx>),
 {
     fn visit_binder<T: TypeFoldable<'tcx>>(&mut self, t: &ty::Binder<T>) -> bool {
         t.skip_binder().visit_with(self);
@@ -509,7 +732,7 @@ impl<'tcx> TypeVisitor<'tcx> for OpaqueTypeOutlivesVisitor<'_, '_, 'tcx>
             // ignore bound regions, keep visiting
             ty::ReLateBound(_, _) => false,
             _ => {
-                self.infcx.sub_regions(infer::CallReturn(self.span), self.least_region, r);
+                (self.op)(r);
                 false
             }
         }
@@ -525,23 +748,23 @@ impl<'tcx> TypeVisitor<'tcx> for OpaqueTypeOutlivesVisitor<'_, '_, 'tcx>
             ty::Closure(def_id, ref substs) => {
                 // Skip lifetime parameters of the enclosing item(s)
 
-                for upvar_ty in substs.upvar_tys(def_id, self.infcx.tcx) {
+                for upvar_ty in substs.upvar_tys(def_id, self.tcx) {
                     upvar_ty.visit_with(self);
                 }
 
-                substs.closure_sig_ty(def_id, self.infcx.tcx).visit_with(self);
+                substs.closure_sig_ty(def_id, self.tcx).visit_with(self);
             }
 
             ty::Generator(def_id, ref substs, _) => {
                 // Skip lifetime parameters of the enclosing item(s)
                 // Also skip the witness type, because that has no free regions.
 
-                for upvar_ty in substs.upvar_tys(def_id, self.infcx.tcx) {
+                for upvar_ty in substs.upvar_tys(def_id, self.tcx) {
                     upvar_ty.visit_with(self);
                 }
 
-                substs.return_ty(def_id, self.infcx.tcx).visit_with(self);
-                substs.yield_ty(def_id, self.infcx.tcx).visit_with(self);
+                substs.return_ty(def_id, self.tcx).visit_with(self);
+                substs.yield_ty(def_id, self.tcx).visit_with(self);
             }
             _ => {
                 ty.super_visit_with(self);
@@ -552,6 +775,243 @@ impl<'tcx> TypeVisitor<'tcx> for OpaqueTypeOutlivesVisitor<'_, '_, 'tcx>
     }
 }
 
+/// Why `find_least_region` could not pick a least region.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum LeastRegionError<'tcx> {
+    /// Neither `a` nor `b` outlives the other.
+    Unrelated { a: ty::Region<'tcx>, b: ty::Region<'tcx> },
+    /// Like `Unrelated`, but both regions are elided (`'_`), so naming
+    /// them would not help.
+    ElidedUnrelated,
+}
+
+/// Finds the least of `candidate_regions`, i.e., the one that all of the
+/// others outlive, or `None` if there are no candidates. Fails if two of the
+/// candidates are unrelated.
+fn find_least_region<'tcx, FRR: FreeRegionRelations<'tcx>>(
+    candidate_regions: &[ty::Region<'tcx>],
+    free_region_relations: &FRR,
+) -> Result<Option<ty::Region<'tcx>>, LeastRegionError<'tcx>> {
+    let mut least_region = None;
+    for &subst_arg in candidate_regions {
+        // Compute the least upper bound of it with the other regions.
+        debug!("find_least_region: least_region={:?}", least_region);
+        debug!("find_least_region: subst_arg={:?}", subst_arg);
+        match least_region {
+            None => least_region = Some(subst_arg),
+            Some(lr) => {
+                if free_region_relations.sub_free_regions(lr, subst_arg) {
+                    // keep the current least region
+                } else if free_region_relations.sub_free_regions(subst_arg, lr) {
+                    // switch to `subst_arg`
+                    least_region = Some(subst_arg);
+                } else {
+                    return Err(if is_elided(lr) && is_elided(subst_arg) {
+                        LeastRegionError::ElidedUnrelated
+                    } else {
+                        LeastRegionError::Unrelated { a: lr, b: subst_arg }
+                    });
+                }
+            }
+        }
+    }
+    Ok(least_region)
+}
+
+/// Whether `region` is an elided lifetime (written `'_` or left out).
+fn is_elided(region: ty::Region<'_>) -> bool {
+    match *region {
+        ty::ReEarlyBound(data) => !data.has_name(),
+        ty::ReFree(ty::FreeRegion { bound_region, .. }) => match bound_region {
+            ty::BrAnon(_) => true,
+            ty::BrNamed(_, name) => name == kw::UnderscoreLifetime.as_interned_str(),
+            ty::BrEnv => false,
+        },
+        _ => false,
+    }
+}
+
+/// Describes where an opaque type came from, for use in diagnostics,
+/// e.g., "ambiguous lifetime bound in `impl Trait`".
+pub fn origin_description(origin: hir::ExistTyOrigin) -> &'static str {
+    match origin {
+        hir::ExistTyOrigin::ExistentialType => "existential type",
+        hir::ExistTyOrigin::ReturnImplTrait => "impl Trait",
+        hir::ExistTyOrigin::AsyncFn => "async fn",
+    }
+}
+
+/// Returns the function whose return type is the anonymous opaque type
+/// `def_id`, if any.
+fn opaque_parent_fn(tcx: TyCtxt<'_, '_>, def_id: DefId) -> Option<DefId> {
+    let hir_id = tcx.hir().as_local_hir_id(def_id)?;
+    match tcx.hir().find_by_hir_id(hir_id) {
+        Some(Node::Item(item)) => match item.node {
+            hir::ItemKind::Existential(hir::ExistTy { impl_trait_fn, .. }) => impl_trait_fn,
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Suggests rewriting the function `fn_def_id` so that all of the references
+/// with elided lifetimes in its arguments use the same named lifetime. That
+/// lifetime is the function's sole lifetime parameter, if it has one, or a
+/// new `'a` (or similar) otherwise. References with explicit lifetimes are
+/// left alone, and nothing is suggested if the function has several lifetime
+/// parameters, as a single lifetime cannot be picked for it then.
+pub fn suggest_single_named_lifetime(
+    tcx: TyCtxt<'_, '_>,
+    fn_def_id: DefId,
+    err: &mut DiagnosticBuilder<'_>,
+) {
+    let (decl, generics) = match (
+        tcx.hir().as_local_hir_id(fn_def_id).and_then(|id| tcx.hir().fn_decl_by_hir_id(id)),
+        tcx.hir().get_generics(fn_def_id),
+    ) {
+        (Some(decl), Some(generics)) => (decl, generics),
+        _ => return,
+    };
+
+    let named: Vec<_> = generics.params.iter().filter_map(|param| match param.kind {
+        hir::GenericParamKind::Lifetime { kind: hir::LifetimeParamKind::Explicit } |
+        hir::GenericParamKind::Lifetime { kind: hir::LifetimeParamKind::InBand } => {
+            Some(param.name.ident().to_string())
+        }
+        _ => None,
+    }).collect();
+    let mut parts = vec![];
+    let name = match &named[..] {
+        [] => {
+            // The function has no lifetime parameters, so none can be named `'a`.
+            let name = "'a".to_string();
+            if generics.span.lo() == generics.span.hi() {
+                parts.push((generics.span, format!("<{}>", name)));
+            } else {
+                let lo = generics.span.lo() + BytePos(1);
+                parts.push((generics.span.with_lo(lo).with_hi(lo), format!("{}, ", name)));
+            }
+            name
+        }
+        [name] => name.clone(),
+        _ => return,
+    };
+
+    // Both `&u8` and `&'_ u8` are lowered to fresh lifetimes in `async fn`
+    // arguments, so look at the source to tell them apart from `&'b u8`.
+    let source_map = tcx.sess.source_map();
+    let mut finder = RefTyFinder { spans: vec![] };
+    for input in &decl.inputs {
+        finder.visit_ty(input);
+    }
+    let mut rewrote_any = false;
+    for span in finder.spans {
+        let snippet = match source_map.span_to_snippet(span) {
+            Ok(snippet) => snippet,
+            Err(_) => return,
+        };
+        if !snippet.starts_with('&') {
+            return;
+        }
+        let after_amp = snippet[1..].trim_start();
+        let is_underscore = after_amp.starts_with("'_") &&
+            !after_amp[2..].starts_with(|c: char| c.is_alphanumeric() || c == '_');
+        if is_underscore {
+            let lo = span.lo() + BytePos((snippet.len() - after_amp.len()) as u32);
+            parts.push((span.with_lo(lo).with_hi(lo + BytePos(2)), name.clone()));
+        } else if !after_amp.starts_with('\'') {
+            let amp = span.with_hi(span.lo() + BytePos(1));
+            parts.push((amp, format!("&{} ", name)));
+        } else {
+            continue;
+        }
+        rewrote_any = true;
+    }
+    if !rewrote_any {
+        return;
+    }
+
+    err.multipart_suggestion(
+        &format!("consider using the named lifetime `{}` for all references", name),
+        parts,
+        Applicability::MachineApplicable,
+    );
+}
+
+/// Collects the spans of the reference types in a function's arguments,
+/// not counting those in function pointer types, which have their own
+/// elision rules.
+struct RefTyFinder {
+    spans: Vec<Span>,
+}
+
+impl<'v> Visitor<'v> for RefTyFinder {
+    fn nested_visit_map<'this>(&'this mut self) -> NestedVisitorMap<'this, 'v> {
+        NestedVisitorMap::None
+    }
+
+    fn visit_ty(&mut self, ty: &'v hir::Ty) {
+        match ty.node {
+            hir::TyKind::BareFn(_) => return,
+            hir::TyKind::Rptr(..) => self.spans.push(ty.span),
+            _ => {}
+        }
+        intravisit::walk_ty(self, ty);
+    }
+}
+
+impl<'gcx, 'tcx> TyCtxt<'gcx, 'tcx> {
+    /// Returns `true` if `def_id` is the opaque type standing for the return
+    /// type of an `async fn`. The origin of opaque types is only known for the
+    /// local crate, so this is always `false` for opaque types from other crates.
+    pub fn is_async_fn_opaque(self, def_id: DefId) -> bool {
+        let hir_id = match self.hir().as_local_hir_id(def_id) {
+            Some(hir_id) => hir_id,
+            None => return false,
+        };
+        match self.hir().find_by_hir_id(hir_id) {
+            Some(Node::Item(item)) => match item.node {
+                hir::ItemKind::Existential(hir::ExistTy {
+                    origin: hir::ExistTyOrigin::AsyncFn,
+                    ..
+                }) => true,
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+}
+
+/// Folder for `InferCtxt::expand_opaque_types`.
+struct OpaqueTypeExpander<'a, 'gcx: 'tcx, 'tcx: 'a> {
+    infcx: &'a InferCtxt<'a, 'gcx, 'tcx>,
+    opaque_types: &'a OpaqueTypeMap<'tcx>,
+    /// The opaque types currently being expanded, to detect recursive ones.
+    expanding: FxHashSet<DefId>,
+}
+
+impl<'a, 'gcx, 'tcx> TypeFolder<'gcx, 'tcx> for OpaqueTypeExpander<'a, 'gcx, 'tcx> {
+    fn tcx(&self) -> TyCtxt<'gcx, 'tcx> {
+        self.infcx.tcx
+    }
+
+    fn fold_ty(&mut self, ty: Ty<'tcx>) -> Ty<'tcx> {
+        if let ty::Opaque(def_id, _) = ty.sty {
+            if let Some(opaque_defn) = self.opaque_types.get(&def_id) {
+                if !self.expanding.insert(def_id) {
+                    return ty;
+                }
+                let concrete_ty = self.infcx.resolve_vars_if_possible(&opaque_defn.concrete_ty);
+                let expanded_ty = concrete_ty.fold_with(self);
+                self.expanding.remove(&def_id);
+                return expanded_ty;
+            }
+        }
+
+        ty.super_fold_with(self)
+    }
+}
+
 struct ReverseMapper<'gcx, 'tcx> {
     tcx: TyCtxt<'gcx, 'tcx>,
 
@@ -560,6 +1020,7 @@ struct ReverseMapper<'gcx, 'tcx> {
     tainted_by_errors: bool,
 
     opaque_type_def_id: DefId,
+    origin: hir::ExistTyOrigin,
     map: FxHashMap<Kind<'tcx>, Kind<'gcx>>,
     map_missing_regions_to_empty: bool,
 
@@ -572,6 +1033,7 @@ impl ReverseMapper<'gcx, 'tcx> {
         tcx: TyCtxt<'gcx, 'tcx>,
         tainted_by_errors: bool,
         opaque_type_def_id: DefId,
+        origin: hir::ExistTyOrigin,
         map: FxHashMap<Kind<'tcx>, Kind<'gcx>>,
         hidden_ty: Ty<'tcx>,
     ) -> Self {
@@ -579,6 +1041,7 @@ impl ReverseMapper<'gcx, 'tcx> {
             tcx,
             tainted_by_errors,
             opaque_type_def_id,
+            origin,
             map,
             map_missing_regions_to_empty: false,
             hidden_ty: Some(hidden_ty),
@@ -627,8 +1090,9 @@ impl TypeFolder<'gcx, 'tcx> for ReverseMapper<'gcx, 'tcx> {
                             self.tcx.sess,
                             span,
                             E0700,
-                            "hidden type for `impl Trait` captures lifetime that \
+                            "hidden type for `{}` captures lifetime that \
                              does not appear in bounds",
+                            origin_description(self.origin),
                         );
 
                         // Assuming regionck succeeded, then we must
@@ -651,6 +1115,25 @@ impl TypeFolder<'gcx, 'tcx> for ReverseMapper<'gcx, 'tcx> {
                             ""
                         );
 
+                        // Only suggest `'static` if, apart from `'static`, the
+                        // hidden type mentions `r` and nothing else. If it also
+                        // mentions a region from the opaque type's substs, it
+                        // borrows through that region and the suggestion would
+                        // be wrong.
+                        let borrows_nothing = !self.tcx.any_free_region_meets(&hidden_ty, |r2| {
+                            r2 != r && *r2 != ty::ReStatic
+                        });
+                        if borrows_nothing {
+                            if let Ok(snippet) = self.tcx.sess.source_map().span_to_snippet(span) {
+                                err.span_suggestion(
+                                    span,
+                                    "if it borrows nothing, require it to be `'static`",
+                                    format!("{} + 'static", snippet),
+                                    Applicability::MaybeIncorrect,
+                                );
+                            }
+                        }
+
                         err.emit();
                     }
                 }
@@ -731,6 +1214,10 @@ struct Instantiator<'a, 'gcx: 'tcx, 'tcx: 'a> {
     param_env: ty::ParamEnv<'tcx>,
     opaque_types: OpaqueTypeMap<'tcx>,
     obligations: Vec<PredicateObligation<'tcx>>,
+    /// How many opaque types we are currently nested inside of while
+    /// instantiating their bounds, and the outermost such type.
+    nesting_depth: usize,
+    outermost_opaque: Option<DefId>,
 }
 
 impl<'a, 'gcx, 'tcx> Instantiator<'a, 'gcx, 'tcx> {
@@ -824,7 +1311,7 @@ impl<'a, 'gcx, 'tcx> Instantiator<'a, 'gcx, 'tcx> {
                             ),
                         };
                         if in_definition_scope {
-                            return self.fold_opaque_ty(ty, def_id, substs, origin);
+                            return self.fold_opaque_ty(def_id, substs, origin);
                         }
 
                         debug!(
@@ -845,7 +1332,6 @@ impl<'a, 'gcx, 'tcx> Instantiator<'a, 'gcx, 'tcx> {
 
     fn fold_opaque_ty(
         &mut self,
-        ty: Ty<'tcx>,
         def_id: DefId,
         substs: SubstsRef<'tcx>,
         origin: hir::ExistTyOrigin,
@@ -863,6 +1349,26 @@ impl<'a, 'gcx, 'tcx> Instantiator<'a, 'gcx, 'tcx> {
         if let Some(opaque_defn) = self.opaque_types.get(&def_id) {
             return opaque_defn.concrete_ty;
         }
+
+        // Guard against runaway expansion of nested `impl Trait` bounds,
+        // reporting it at the outermost opaque type we started from.
+        let max_nesting = tcx.sess.opts.debugging_opts.opaque_max_nesting;
+        if self.nesting_depth >= max_nesting {
+            let outermost = self.outermost_opaque.unwrap_or(def_id);
+            let mut diag = tcx.sess.struct_span_fatal(
+                tcx.def_span(outermost),
+                "reached the nesting limit while instantiating `impl Trait` types",
+            );
+            diag.note(&format!(
+                "the limit of {} nested `impl Trait` types can be raised with \
+                 `-Z opaque-max-nesting`",
+                max_nesting,
+            ));
+            diag.emit();
+            tcx.sess.abort_if_errors();
+            return tcx.types.err;
+        }
+
         let span = tcx.def_span(def_id);
         let ty_var = infcx.next_ty_var(TypeVariableOrigin {
             kind: TypeVariableOriginKind::TypeInference,
@@ -877,7 +1383,7 @@ impl<'a, 'gcx, 'tcx> Instantiator<'a, 'gcx, 'tcx> {
         let bounds = predicates_of.instantiate(tcx, substs);
         debug!("instantiate_opaque_types: bounds={:?}", bounds);
 
-        let required_region_bounds = tcx.required_region_bounds(ty, bounds.predicates.clone());
+        let required_region_bounds = infcx.opaque_required_region_bounds(def_id, substs);
         debug!(
             "instantiate_opaque_types: required_region_bounds={:?}",
             required_region_bounds
@@ -890,10 +1396,16 @@ impl<'a, 'gcx, 'tcx> Instantiator<'a, 'gcx, 'tcx> {
             "instantiate_opaque_types: param_env={:#?}",
             self.param_env,
         );
-        debug!(
-            "instantiate_opaque_types: generics={:#?}",
-            tcx.generics_of(def_id),
-        );
+        let generics = tcx.generics_of(def_id);
+        debug!("instantiate_opaque_types: generics={:#?}", generics);
+        let lifetime_param_count = generics
+            .params
+            .iter()
+            .filter(|param| match param.kind {
+                GenericParamDefKind::Lifetime => true,
+                _ => false,
+            })
+            .count();
 
         self.opaque_types.insert(
             def_id,
@@ -901,11 +1413,17 @@ impl<'a, 'gcx, 'tcx> Instantiator<'a, 'gcx, 'tcx> {
                 substs,
                 concrete_ty: ty_var,
                 has_required_region_bounds: !required_region_bounds.is_empty(),
+                lifetime_param_count,
                 origin,
             },
         );
         debug!("instantiate_opaque_types: ty_var={:?}", ty_var);
 
+        if self.nesting_depth == 0 {
+            self.outermost_opaque = Some(def_id);
+        }
+        self.nesting_depth += 1;
+
         self.obligations.reserve(bounds.predicates.len());
         for predicate in bounds.predicates {
             // Change the predicate to refer to the type variable,
@@ -921,6 +1439,8 @@ impl<'a, 'gcx, 'tcx> Instantiator<'a, 'gcx, 'tcx> {
                 .push(traits::Obligation::new(cause, self.param_env, predicate));
         }
 
+        self.nesting_depth -= 1;
+
         ty_var
     }
 }
@@ -966,3 +1486,82 @@ pub fn may_define_existential_type(
     // Syntactically, we are allowed to define the concrete type if:
     hir_id == scope
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::infer::outlives::free_region_map::FreeRegionRelations;
+    use crate::hir::def_id::{DefId, CRATE_DEF_INDEX};
+    use crate::ty::{self, RegionVid};
+    use super::{find_least_region, LeastRegionError};
+
+    /// Relates regions by an explicit list of `(shorter, longer)` pairs;
+    /// every region is also related to itself and to `'static`.
+    struct Outlives<'a, 'tcx>(&'a [(ty::Region<'tcx>, ty::Region<'tcx>)]);
+
+    impl FreeRegionRelations<'tcx> for Outlives<'_, 'tcx> {
+        fn sub_free_regions(&self, shorter: ty::Region<'tcx>, longer: ty::Region<'tcx>) -> bool {
+            shorter == longer || *longer == ty::ReStatic || self.0.contains(&(shorter, longer))
+        }
+    }
+
+    #[test]
+    fn unique_least_region() {
+        let (a, b, c) = (
+            ty::ReVar(RegionVid::from_u32(0)),
+            ty::ReVar(RegionVid::from_u32(1)),
+            ty::ReVar(RegionVid::from_u32(2)),
+        );
+        let outlives = Outlives(&[(&a, &b), (&a, &c)]);
+        assert_eq!(find_least_region(&[&b, &a, &c], &outlives).ok(), Some(Some(&a)));
+        assert_eq!(find_least_region(&[&a, &b, &c], &outlives).ok(), Some(Some(&a)));
+    }
+
+    #[test]
+    fn no_candidate_regions() {
+        // The caller falls back to `'static` in this case.
+        assert_eq!(find_least_region(&[], &Outlives(&[])).ok(), Some(None));
+    }
+
+    #[test]
+    fn static_is_never_least() {
+        let a = ty::ReVar(RegionVid::from_u32(0));
+        let outlives = Outlives(&[]);
+        assert_eq!(
+            find_least_region(&[&ty::ReStatic, &a], &outlives).ok(),
+            Some(Some(&a)),
+        );
+        assert_eq!(
+            find_least_region(&[&ty::ReStatic], &outlives).ok(),
+            Some(Some(&ty::ReStatic)),
+        );
+    }
+
+    #[test]
+    fn unrelated_regions() {
+        let (a, b) = (ty::ReVar(RegionVid::from_u32(0)), ty::ReVar(RegionVid::from_u32(1)));
+        assert_eq!(
+            find_least_region(&[&a, &b], &Outlives(&[])),
+            Err(LeastRegionError::Unrelated { a: &a, b: &b }),
+        );
+    }
+
+    #[test]
+    fn unrelated_elided_regions() {
+        let anon = |n| ty::ReFree(ty::FreeRegion {
+            scope: DefId::local(CRATE_DEF_INDEX),
+            bound_region: ty::BrAnon(n),
+        });
+        let (a, b) = (anon(0), anon(1));
+        assert_eq!(
+            find_least_region(&[&a, &b], &Outlives(&[])),
+            Err(LeastRegionError::ElidedUnrelated),
+        );
+
+        // Only one of the two is elided, so the error names both.
+        let c = ty::ReVar(RegionVid::from_u32(0));
+        assert_eq!(
+            find_least_region(&[&a, &c], &Outlives(&[])),
+            Err(LeastRegionError::Unrelated { a: &a, b: &c }),
+        );
+    }
+}
@@ -55,6 +55,15 @@ pub trait FreeRegionRelations<'tcx> {
     /// Tests whether `r_a <= r_b`. Both must be free regions or
     /// `'static`.
     fn sub_free_regions(&self, shorter: ty::Region<'tcx>, longer: ty::Region<'tcx>) -> bool;
+
+    /// Tests whether `r_a` and `r_b` are provably equal, i.e., whether each
+    /// outlives the other (as for `'a` and `'b` given `'a: 'b, 'b: 'a`). Both
+    /// must be free regions or `'static`; in particular, bound regions are
+    /// rejected (`FreeRegionMap` asserts that they do not occur), since they
+    /// must be instantiated before they can be compared.
+    fn eq_free_regions(&self, r_a: ty::Region<'tcx>, r_b: ty::Region<'tcx>) -> bool {
+        self.sub_free_regions(r_a, r_b) && self.sub_free_regions(r_b, r_a)
+    }
 }
 
 impl<'tcx> FreeRegionRelations<'tcx> for FreeRegionMap<'tcx> {
@@ -96,3 +105,45 @@ impl<'a, 'tcx> Lift<'tcx> for FreeRegionMap<'a> {
                      .map(|relation| FreeRegionMap { relation })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::hir::def_id::{DefId, CRATE_DEF_INDEX, LOCAL_CRATE};
+    use crate::ty::{self, BoundRegion};
+    use super::{FreeRegionMap, FreeRegionRelations};
+
+    fn free_region(index: u32) -> ty::RegionKind {
+        ty::ReFree(ty::FreeRegion {
+            scope: DefId { krate: LOCAL_CRATE, index: CRATE_DEF_INDEX },
+            bound_region: BoundRegion::BrAnon(index),
+        })
+    }
+
+    #[test]
+    fn eq_free_regions() {
+        let (a, b, c) = (free_region(0), free_region(1), free_region(2));
+        let mut map = FreeRegionMap::default();
+        map.relate_regions(&a, &b);
+        map.relate_regions(&b, &a);
+        map.relate_regions(&a, &c);
+        map.relate_regions(&ty::ReStatic, &c);
+
+        assert!(map.eq_free_regions(&a, &a));
+        assert!(map.eq_free_regions(&a, &b));
+        assert!(map.eq_free_regions(&b, &a));
+        // Only `'a <= 'c` is known, not `'c <= 'a`.
+        assert!(!map.eq_free_regions(&a, &c));
+        assert!(!map.eq_free_regions(&c, &a));
+        // `'c: 'static` makes `'c` equal to `'static`.
+        assert!(map.eq_free_regions(&c, &ty::ReStatic));
+        assert!(!map.eq_free_regions(&a, &ty::ReStatic));
+    }
+
+    #[test]
+    #[should_panic]
+    fn eq_free_regions_rejects_bound_regions() {
+        let a = free_region(0);
+        let bound = ty::ReLateBound(ty::INNERMOST, BoundRegion::BrAnon(0));
+        FreeRegionMap::default().eq_free_regions(&a, &bound);
+    }
+}
@@ -25,7 +25,7 @@ use std::iter::FusedIterator;
 use std::ops::{Index, IndexMut};
 use std::slice;
 use std::vec::IntoIter;
-use std::{iter, mem, option, u32};
+use std::{cmp, iter, mem, option, u32};
 use syntax::ast::Name;
 use syntax::symbol::{InternedString, Symbol};
 use syntax_pos::{Span, DUMMY_SP};
@@ -3139,6 +3139,15 @@ pub enum ConstraintCategory {
     Internal,
 }
 
+impl ConstraintCategory {
+    /// When a single constraint arises from more than one category (e.g., a
+    /// cast whose result is returned), picks the one that explains it best
+    /// to the user -- that is, the one that sorts first.
+    pub fn max_by_blame(a: ConstraintCategory, b: ConstraintCategory) -> ConstraintCategory {
+        cmp::min(a, b)
+    }
+}
+
 /// The subject of a ClosureOutlivesRequirement -- that is, the thing
 /// that must outlive some region.
 #[derive(Copy, Clone, Debug, RustcEncodable, RustcDecodable, HashStable)]
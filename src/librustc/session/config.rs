@@ -1367,10 +1367,16 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
         "in addition to `.mir` files, create graphviz `.dot` files"),
     dump_mir_exclude_pass_number: bool = (false, parse_bool, [UNTRACKED],
         "if set, exclude the pass number when dumping MIR (used in tests)"),
+    dump_nll_constraints: bool = (false, parse_bool, [UNTRACKED],
+        "print the region constraints collected by the MIR type-checker for each body"),
+    dump_opaque_types: bool = (false, parse_bool, [UNTRACKED],
+        "print the `impl Trait` types instantiated in each function body after type checking"),
     mir_emit_retag: bool = (false, parse_bool, [TRACKED],
         "emit Retagging MIR statements, interpreted e.g., by miri; implies -Zmir-opt-level=0"),
     perf_stats: bool = (false, parse_bool, [UNTRACKED],
         "print some performance-related statistics"),
+    place_conflict_stats: bool = (false, parse_bool, [UNTRACKED],
+        "print how often each pair of projections is compared when checking places for conflicts"),
     query_stats: bool = (false, parse_bool, [UNTRACKED],
         "print some statistics about the query system"),
     hir_stats: bool = (false, parse_bool, [UNTRACKED],
@@ -1487,6 +1493,8 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
     symbol_mangling_version: SymbolManglingVersion = (SymbolManglingVersion::Legacy,
         parse_symbol_mangling_version, [TRACKED],
         "which mangling version to use for symbol names"),
+    opaque_max_nesting: usize = (256, parse_uint, [TRACKED],
+        "the maximum depth of nested `impl Trait` types to instantiate (default: 256)"),
 }
 
 pub fn default_lib_output() -> CrateType {
@@ -3348,6 +3356,12 @@ mod tests {
         assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
         opts.debugging_opts.dump_mir_graphviz = true;
         assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.dump_nll_constraints = true;
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.dump_opaque_types = true;
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
+        opts.debugging_opts.place_conflict_stats = true;
+        assert_eq!(reference.dep_tracking_hash(), opts.dep_tracking_hash());
 
         // Make sure changing a [TRACKED] option changes the hash
         opts = reference.clone();
@@ -3398,6 +3412,10 @@ mod tests {
         opts.debugging_opts.mir_opt_level = 3;
         assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
 
+        opts = reference.clone();
+        opts.debugging_opts.opaque_max_nesting = 8;
+        assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
+
         opts = reference.clone();
         opts.debugging_opts.relro_level = Some(RelroLevel::Full);
         assert!(reference.dep_tracking_hash() != opts.dep_tracking_hash());
@@ -185,6 +185,12 @@ pub struct PerfStats {
     pub normalize_ty_after_erasing_regions: AtomicUsize,
     /// Number of times this query is invoked.
     pub normalize_projection_ty: AtomicUsize,
+    /// Number of times each pair of projection kinds was compared when checking
+    /// places for conflicts. Only recorded with `-Z place-conflict-stats`.
+    pub place_conflict_projection_pairs: Lock<FxHashMap<(&'static str, &'static str), usize>>,
+    /// Number of times different fields of a union were compared when checking
+    /// places for conflicts. Only recorded with `-Z place-conflict-stats`.
+    pub place_conflict_union_fields: AtomicUsize,
 }
 
 /// Enum to support dispatch of one-time diagnostics (in Session.diag_once)
@@ -867,6 +873,19 @@ impl Session {
                  self.perf_stats.normalize_projection_ty.load(Ordering::Relaxed));
     }
 
+    pub fn print_place_conflict_stats(&self) {
+        let pairs = self.perf_stats.place_conflict_projection_pairs.lock();
+        let mut pairs: Vec<_> = pairs.iter().collect();
+        pairs.sort();
+
+        println!("place-conflict-stats projection pairs:");
+        for ((elem1, elem2), count) in pairs {
+            println!("place-conflict-stats     {}/{}: {}", elem1, elem2, count);
+        }
+        println!("place-conflict-stats different union fields: {}",
+                 self.perf_stats.place_conflict_union_fields.load(Ordering::Relaxed));
+    }
+
     /// We want to know if we're allowed to do an optimization for crate foo from -z fuel=foo=n.
     /// This expends fuel if applicable, and records fuel if applicable.
     pub fn consider_optimizing<T: Fn() -> String>(&self, crate_name: &str, msg: T) -> bool {
@@ -1252,6 +1271,8 @@ fn build_session_(
             queries_canonicalized: AtomicUsize::new(0),
             normalize_ty_after_erasing_regions: AtomicUsize::new(0),
             normalize_projection_ty: AtomicUsize::new(0),
+            place_conflict_projection_pairs: Lock::new(FxHashMap::default()),
+            place_conflict_union_fields: AtomicUsize::new(0),
         },
         code_stats: Default::default(),
         optimization_fuel_crate,
@@ -563,7 +563,7 @@ impl<'tcx> LayoutCx<'tcx, TyCtxt<'tcx, 'tcx>> {
 
                 let count = count.assert_usize(tcx).ok_or(LayoutError::Unknown(ty))?;
                 let element = self.layout_of(element)?;
-                let size = element.size.checked_mul(count, dl)
+                let size = element.size.repeat(count, dl)
                     .ok_or(LayoutError::SizeOverflow(ty))?;
 
                 let abi = if count != 0 && ty.conservative_is_privately_uninhabited(tcx) {
@@ -657,7 +657,7 @@ impl<'tcx> LayoutCx<'tcx, TyCtxt<'tcx, 'tcx>> {
                                                 ty, element.ty));
                     }
                 };
-                let size = element.size.checked_mul(count, dl)
+                let size = element.size.repeat(count, dl)
                     .ok_or(LayoutError::SizeOverflow(ty))?;
                 let align = dl.vector_align(size);
                 let size = size.align_to(align.abi);
@@ -1014,7 +1014,7 @@ impl<'tcx> LayoutCx<'tcx, TyCtxt<'tcx, 'tcx>> {
                 // Align the maximum variant size to the largest alignment.
                 size = size.align_to(align.abi);
 
-                if size.bytes() >= dl.obj_size_bound() {
+                if dl.obj_size_bound().map_or(true, |bound| size.bytes() >= bound) {
                     return Err(LayoutError::SizeOverflow(ty));
                 }
 
@@ -2647,7 +2647,7 @@ where
             }
 
             // Only pointer types handled below.
-            if scalar.value != Pointer {
+            if !scalar.value.is_ptr() {
                 return;
             }
 
@@ -349,7 +349,7 @@ impl<'a, 'tcx: 'a, V: CodegenObject> PlaceRef<'tcx, V> {
         // as this will yield the lowest alignment.
         let layout = self.layout.field(bx, 0);
         let offset = if bx.is_const_integral(llindex) {
-            layout.size.checked_mul(bx.const_to_uint(llindex), bx).unwrap_or(layout.size)
+            layout.size.repeat(bx.const_to_uint(llindex), bx).unwrap_or(layout.size)
         } else {
             layout.size
         };
@@ -365,6 +365,10 @@ pub fn run_compiler(
             sess.print_perf_stats();
         }
 
+        if sess.opts.debugging_opts.place_conflict_stats {
+            sess.print_place_conflict_stats();
+        }
+
         if sess.print_fuel_crate.is_some() {
             eprintln!("Fuel used by {}: {}",
                 sess.print_fuel_crate.as_ref().unwrap(),
@@ -14,6 +14,7 @@ use syntax::source_map::CompilerDesugaringKind;
 
 use super::nll::explain_borrow::BorrowExplanation;
 use super::nll::region_infer::{RegionName, RegionNameSource};
+use super::places_conflict;
 use super::prefixes::IsPrefixOf;
 use super::WriteKind;
 use super::borrow_set::BorrowData;
@@ -589,12 +590,6 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
         first_borrowed_place: &Place<'tcx>,
         second_borrowed_place: &Place<'tcx>,
     ) -> (String, String, String, String) {
-        // Define a small closure that we can use to check if the type of a place
-        // is a union.
-        let union_ty = |place: &Place<'tcx>| -> Option<Ty<'tcx>> {
-            let ty = place.ty(self.body, self.infcx.tcx).ty;
-            ty.ty_adt_def().filter(|adt| adt.is_union()).map(|_| ty)
-        };
         let describe_place = |place| self.describe_place(place).unwrap_or_else(|| "_".to_owned());
 
         // Start with an empty tuple, so we can use the functions on `Option` to reduce some
@@ -607,46 +602,32 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
                 first_borrowed_place != second_borrowed_place
             })
             .and_then(|_| {
-                // We're going to want to traverse the first borrowed place to see if we can find
-                // field access to a union. If we find that, then we will keep the place of the
-                // union being accessed and the field that was being accessed so we can check the
-                // second borrowed place for the same union and a access to a different field.
-                let mut current = first_borrowed_place;
-                while let Place::Projection(box Projection { base, elem }) = current {
-                    match elem {
-                        ProjectionElem::Field(field, _) if union_ty(base).is_some() => {
-                            return Some((base, field));
-                        },
-                        _ => current = base,
-                    }
-                }
-                None
+                // Walk both places in lockstep, like the conflict check itself does, to find
+                // the union (if any) whose different fields the two places go through.
+                places_conflict::union_field_conflict(
+                    self.infcx.tcx,
+                    self.body,
+                    first_borrowed_place,
+                    second_borrowed_place,
+                )
             })
-            .and_then(|(target_base, target_field)| {
-                // With the place of a union and a field access into it, we traverse the second
-                // borrowed place and look for a access to a different field of the same union.
-                let mut current = second_borrowed_place;
-                while let Place::Projection(box Projection { base, elem }) = current {
-                    if let ProjectionElem::Field(field, _) = elem {
-                        if let Some(union_ty) = union_ty(base) {
-                            if field != target_field && base == target_base {
-                                return Some((
-                                    describe_place(base),
-                                    describe_place(first_borrowed_place),
-                                    describe_place(second_borrowed_place),
-                                    union_ty.to_string(),
-                                ));
-                            }
-                        }
-                    }
-
-                    current = base;
-                }
-                None
+            .map(|conflict| {
+                (
+                    describe_place(&conflict.union_place),
+                    describe_place(first_borrowed_place),
+                    describe_place(second_borrowed_place),
+                    conflict.union_ty.to_string(),
+                )
             })
             .unwrap_or_else(|| {
                 // If we didn't find a field access into a union, or both places match, then
-                // only return the description of the first place.
+                // only return the description of the first place. Fields of transparent
+                // wrappers are reported as the wrapper itself, as they overlap it exactly.
+                let first_borrowed_place = places_conflict::strip_transparent_fields(
+                    self.infcx.tcx,
+                    self.body,
+                    first_borrowed_place,
+                );
                 (
                     describe_place(first_borrowed_place),
                     "".to_string(),
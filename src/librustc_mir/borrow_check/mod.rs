@@ -30,6 +30,7 @@ use std::mem;
 use std::rc::Rc;
 
 use syntax::ast::Name;
+use syntax::symbol::sym;
 use syntax_pos::{Span, DUMMY_SP};
 
 use crate::dataflow::indexes::{BorrowIndex, InitIndex, MoveOutIndex, MovePathIndex};
@@ -39,6 +40,7 @@ use crate::dataflow::DataflowResultsConsumer;
 use crate::dataflow::FlowAtLocation;
 use crate::dataflow::MoveDataParamEnv;
 use crate::dataflow::{do_dataflow, DebugFormatted};
+use crate::dataflow::has_rustc_mir_with;
 use crate::dataflow::EverInitializedPlaces;
 use crate::dataflow::{MaybeInitializedPlaces, MaybeUninitializedPlaces};
 use crate::util::borrowck_errors::{BorrowckErrors, Origin};
@@ -275,6 +277,12 @@ fn do_mir_borrowck<'a, 'gcx, 'tcx>(
     }
     mbcx.analyze_results(&mut state); // entry point for DataflowResultsConsumer
 
+    if has_rustc_mir_with(&attributes, sym::borrowck_live_start).is_some() {
+        let stop_at_call = has_rustc_mir_with(&attributes, sym::stop_at_call)
+            .and_then(|item| item.value_str());
+        mbcx.report_borrow_live_starts(stop_at_call);
+    }
+
     // Convert any reservation warnings into lints.
     let reservation_warnings = mem::replace(&mut mbcx.reservation_warnings, Default::default());
     for (_, (place, span, location, bk, borrow)) in reservation_warnings {
@@ -802,13 +810,13 @@ use self::ReadOrWrite::{Activation, Read, Reservation, Write};
 use self::AccessDepth::{Deep, Shallow};
 
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
-enum ArtificialField {
+crate enum ArtificialField {
     ArrayLength,
     ShallowBorrow,
 }
 
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
-enum AccessDepth {
+crate enum AccessDepth {
     /// From the RFC: "A *shallow* access means that the immediate
     /// fields reached at P are accessed, but references or pointers
     /// found within are not dereferenced. Right now, the only access
@@ -826,6 +834,38 @@ enum AccessDepth {
     Drop,
 }
 
+impl AccessDepth {
+    /// Returns `true` if any borrow that conflicts with an access of depth
+    /// `other` to some place also conflicts with an access of depth `self`
+    /// to the same place (see `places_conflict`).
+    ///
+    /// This is a total preorder:
+    ///
+    /// `Shallow(Some(_))` < `Shallow(None)` < `Drop` < `Deep`
+    ///
+    /// - `Shallow(Some(_))` accesses only an artificial field, so it only
+    ///   conflicts with borrows of the place itself or a prefix of it. Both
+    ///   kinds of artificial field behave the same way, so they subsume
+    ///   each other.
+    /// - `Shallow(None)` additionally conflicts with borrows of data
+    ///   reachable from the place without going through a dereference.
+    /// - `Drop` additionally conflicts with borrows of data behind a
+    ///   `Box` or raw pointer, but not of data behind a `&mut` reference.
+    /// - `Deep` conflicts with borrows of anything reachable from the place.
+    crate fn subsumes(self, other: AccessDepth) -> bool {
+        fn rank(depth: AccessDepth) -> u8 {
+            match depth {
+                Shallow(Some(_)) => 0,
+                Shallow(None) => 1,
+                AccessDepth::Drop => 2,
+                Deep => 3,
+            }
+        }
+
+        rank(self) >= rank(other)
+    }
+}
+
 /// Kind of access to a value: read or write
 /// (For informational purposes only)
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
@@ -2287,13 +2327,18 @@ enum NoMovePathFound {
 }
 
 /// The degree of overlap between 2 places for borrow-checking.
-enum Overlap {
+enum Overlap<'tcx> {
     /// The places might partially overlap - in this case, we give
     /// up and say that they might conflict. This occurs when
     /// different fields of a union are borrowed. For example,
     /// if `u` is a union, we have no way of telling how disjoint
     /// `u.a.x` and `a.b.y` are.
-    Arbitrary,
+    Arbitrary {
+        /// The union whose fields overlap.
+        union_def: &'tcx ty::AdtDef,
+        /// The fields of the union accessed by each of the two places.
+        fields: (Field, Field),
+    },
     /// The places have the same type, and are either completely disjoint
     /// or equal - i.e., they can't "partially" overlap as can occur with
     /// unions. This is the "base case" on which we recur for extensions
@@ -2303,3 +2348,38 @@ enum Overlap {
     /// will also be disjoint.
     Disjoint,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{AccessDepth, ArtificialField, Deep, Shallow};
+
+    #[test]
+    fn access_depth_subsumes() {
+        let depths = [
+            Shallow(Some(ArtificialField::ArrayLength)),
+            Shallow(Some(ArtificialField::ShallowBorrow)),
+            Shallow(None),
+            AccessDepth::Drop,
+            Deep,
+        ];
+        // `expected[i][j]` is whether `depths[i]` subsumes `depths[j]`.
+        let expected = [
+            [true, true, false, false, false],
+            [true, true, false, false, false],
+            [true, true, true, false, false],
+            [true, true, true, true, false],
+            [true, true, true, true, true],
+        ];
+        for (i, &depth) in depths.iter().enumerate() {
+            for (j, &other) in depths.iter().enumerate() {
+                assert_eq!(
+                    depth.subsumes(other),
+                    expected[i][j],
+                    "{:?}.subsumes({:?})",
+                    depth,
+                    other,
+                );
+            }
+        }
+    }
+}
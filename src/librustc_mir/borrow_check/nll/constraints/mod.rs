@@ -1,5 +1,6 @@
 use rustc::mir::ConstraintCategory;
 use rustc::ty::RegionVid;
+use rustc::util::nodemap::FxHashMap;
 use rustc_data_structures::graph::scc::Sccs;
 use rustc_data_structures::indexed_vec::{Idx, IndexVec};
 use crate::borrow_check::nll::type_check::Locations;
@@ -12,19 +13,51 @@ crate mod graph;
 #[derive(Clone, Default)]
 crate struct ConstraintSet {
     constraints: IndexVec<ConstraintIndex, OutlivesConstraint>,
+
+    /// The constraint added by `push_or_merge` for each `(sup, sub, locations)`
+    /// triple, used to find duplicates. Constraints added with plain `push`
+    /// are not recorded here.
+    by_endpoints: FxHashMap<(RegionVid, RegionVid, Locations), ConstraintIndex>,
 }
 
 impl ConstraintSet {
-    crate fn push(&mut self, constraint: OutlivesConstraint) {
+    /// Adds `constraint` to the set, returning its index, or `None`
+    /// if the constraint was trivial (`'a: 'a`) and hence dropped.
+    crate fn push(&mut self, constraint: OutlivesConstraint) -> Option<ConstraintIndex> {
         debug!(
             "ConstraintSet::push({:?}: {:?} @ {:?}",
             constraint.sup, constraint.sub, constraint.locations
         );
         if constraint.sup == constraint.sub {
             // 'a: 'a is pretty uninteresting
-            return;
+            return None;
+        }
+        Some(self.constraints.push(constraint))
+    }
+
+    /// Like `push`, but if a constraint between the same regions at the
+    /// same locations already exists, keeps that constraint and gives it
+    /// whichever of the two categories is the better explanation (see
+    /// `ConstraintCategory::max_by_blame`).
+    crate fn push_or_merge(&mut self, constraint: OutlivesConstraint) -> Option<ConstraintIndex> {
+        let key = (constraint.sup, constraint.sub, constraint.locations);
+        match self.by_endpoints.get(&key) {
+            Some(&index) => {
+                let existing = &mut self.constraints[index];
+                existing.category =
+                    ConstraintCategory::max_by_blame(existing.category, constraint.category);
+                debug!(
+                    "ConstraintSet::push_or_merge: merged into {:?}, category = {:?}",
+                    index, existing.category
+                );
+                Some(index)
+            }
+            None => {
+                let index = self.push(constraint)?;
+                self.by_endpoints.insert(key, index);
+                Some(index)
+            }
         }
-        self.constraints.push(constraint);
     }
 
     /// Constructs a "normal" graph from the constraint set; the graph makes it
@@ -99,8 +132,50 @@ newtype_index! {
     }
 }
 
+newtype_index! {
+    pub struct QueryConstraintIndex {
+        DEBUG_FORMAT = "QueryConstraintIndex({})"
+    }
+}
+
 newtype_index! {
     pub struct ConstraintSccIndex {
         DEBUG_FORMAT = "ConstraintSccIndex({})"
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustc::mir::Location;
+
+    fn constraint(category: ConstraintCategory) -> OutlivesConstraint {
+        OutlivesConstraint {
+            sup: RegionVid::new(1),
+            sub: RegionVid::new(2),
+            locations: Locations::Single(Location::START),
+            category,
+        }
+    }
+
+    #[test]
+    fn push_or_merge_keeps_better_category() {
+        let mut set = ConstraintSet::default();
+        let first = set.push_or_merge(constraint(ConstraintCategory::Boring));
+        let second = set.push_or_merge(constraint(ConstraintCategory::Return));
+        let third = set.push_or_merge(constraint(ConstraintCategory::Assignment));
+        assert_eq!(first, second);
+        assert_eq!(first, third);
+        assert_eq!(set.len(), 1);
+        assert_eq!(set[first.unwrap()].category, ConstraintCategory::Return);
+    }
+
+    #[test]
+    fn push_does_not_merge() {
+        let mut set = ConstraintSet::default();
+        set.push(constraint(ConstraintCategory::Boring));
+        set.push(constraint(ConstraintCategory::Return));
+        assert_eq!(set.len(), 2);
+        assert!(set.by_endpoints.is_empty());
+    }
+}
@@ -1,38 +1,85 @@
 use std::collections::VecDeque;
 use std::rc::Rc;
 
+use crate::borrow_check::borrow_set::BorrowSet;
 use crate::borrow_check::nll::region_infer::{Cause, RegionInferenceContext};
 use crate::borrow_check::nll::ToRegionVid;
 use crate::util::liveness::{self, DefUse};
 use rustc::mir::visit::{MirVisitable, PlaceContext, Visitor};
-use rustc::mir::{Local, Location, Body};
+use rustc::mir::{Local, Location, Body, Place, PlaceBase};
 use rustc::ty::{RegionVid, TyCtxt};
 use rustc_data_structures::fx::FxHashSet;
 
+/// Searches forward from `start_point`, within the points of `region_vid`,
+/// for the first use that keeps the region live. If `stop_at` is given, the
+/// search neither looks at that location nor expands past it, so `None`
+/// means the region is not used before `stop_at` is reached.
 crate fn find<'tcx>(
     body: &Body<'tcx>,
     regioncx: &Rc<RegionInferenceContext<'tcx>>,
+    borrow_set: &BorrowSet<'tcx>,
     tcx: TyCtxt<'_, 'tcx>,
     region_vid: RegionVid,
     start_point: Location,
+    stop_at: Option<Location>,
 ) -> Option<Cause> {
     let mut uf = UseFinder {
         body,
         regioncx,
+        borrow_set,
         tcx,
         region_vid,
         start_point,
+        stop_at,
+        through_dead_points: false,
+        live_start: None,
     };
 
     uf.find()
 }
 
+/// Like `find`, but the search also goes through points that are not in
+/// `region_vid`. Along with the use, returns the first point of the region
+/// that the search reached, i.e., where the region becomes live on the way
+/// from `start_point` to the use. Uses are still only looked for at points
+/// of the region.
+crate fn find_with_live_start<'tcx>(
+    body: &Body<'tcx>,
+    regioncx: &Rc<RegionInferenceContext<'tcx>>,
+    borrow_set: &BorrowSet<'tcx>,
+    tcx: TyCtxt<'_, 'tcx>,
+    region_vid: RegionVid,
+    start_point: Location,
+    stop_at: Option<Location>,
+) -> (Option<Location>, Option<Cause>) {
+    let mut uf = UseFinder {
+        body,
+        regioncx,
+        borrow_set,
+        tcx,
+        region_vid,
+        start_point,
+        stop_at,
+        through_dead_points: true,
+        live_start: None,
+    };
+
+    let cause = uf.find();
+    (uf.live_start, cause)
+}
+
 struct UseFinder<'cx, 'gcx: 'tcx, 'tcx: 'cx> {
     body: &'cx Body<'tcx>,
     regioncx: &'cx Rc<RegionInferenceContext<'tcx>>,
+    borrow_set: &'cx BorrowSet<'tcx>,
     tcx: TyCtxt<'gcx, 'tcx>,
     region_vid: RegionVid,
     start_point: Location,
+    stop_at: Option<Location>,
+    /// Whether to also search through points that are not in `region_vid`.
+    through_dead_points: bool,
+    /// The first point of `region_vid` the search visited.
+    live_start: Option<Location>,
 }
 
 impl<'cx, 'gcx, 'tcx> UseFinder<'cx, 'gcx, 'tcx> {
@@ -42,7 +89,12 @@ impl<'cx, 'gcx, 'tcx> UseFinder<'cx, 'gcx, 'tcx> {
 
         queue.push_back(self.start_point);
         while let Some(p) = queue.pop_front() {
-            if !self.regioncx.region_contains(self.region_vid, p) {
+            let is_live = self.regioncx.region_contains(self.region_vid, p);
+            if !is_live && !self.through_dead_points {
+                continue;
+            }
+
+            if Some(p) == self.stop_at {
                 continue;
             }
 
@@ -50,12 +102,24 @@ impl<'cx, 'gcx, 'tcx> UseFinder<'cx, 'gcx, 'tcx> {
                 continue;
             }
 
+            if is_live && self.live_start.is_none() {
+                self.live_start = Some(p);
+            }
+
             let block_data = &self.body[p.block];
 
-            match self.def_use(p, block_data.visitable(p.statement_index)) {
+            let def_use = if is_live {
+                self.def_use(p, block_data.visitable(p.statement_index))
+            } else {
+                None
+            };
+            match def_use {
                 Some(DefUseResult::Def) => {}
 
                 Some(DefUseResult::UseLive { local }) => {
+                    if self.is_two_phase_activation(local, p) {
+                        return Some(Cause::Activation(local, p));
+                    }
                     return Some(Cause::LiveVar(local, p));
                 }
 
@@ -85,6 +149,20 @@ impl<'cx, 'gcx, 'tcx> UseFinder<'cx, 'gcx, 'tcx> {
         None
     }
 
+    /// Returns `true` if the use of `local` at `location` activates a
+    /// two-phase borrow stored in `local`.
+    fn is_two_phase_activation(&self, local: Local, location: Location) -> bool {
+        match self.borrow_set.activation_map.get(&location) {
+            Some(borrows) => borrows.iter().any(|&borrow_index| {
+                match self.borrow_set.borrows[borrow_index].assigned_place {
+                    Place::Base(PlaceBase::Local(assigned_local)) => assigned_local == local,
+                    _ => false,
+                }
+            }),
+            None => false,
+        }
+    }
+
     fn def_use(&self, location: Location, thing: &dyn MirVisitable<'tcx>) -> Option<DefUseResult> {
         let mut visitor = DefUseVisitor {
             body: self.body,
@@ -14,6 +14,7 @@ use rustc::ty::adjustment::{PointerCast};
 use rustc_data_structures::fx::FxHashSet;
 use rustc_errors::DiagnosticBuilder;
 use syntax_pos::Span;
+use syntax_pos::symbol::Symbol;
 
 mod find_use;
 
@@ -248,8 +249,11 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
             region_sub
         );
 
-        match find_use::find(body, regioncx, tcx, region_sub, location) {
-            Some(Cause::LiveVar(local, location)) => {
+        match find_use::find(body, regioncx, &self.borrow_set, tcx, region_sub, location, None) {
+            // The activation of a two-phase borrow is explained like any
+            // other later use of the borrow.
+            Some(Cause::LiveVar(local, location))
+            | Some(Cause::Activation(local, location)) => {
                 let span = body.source_info(location).span;
                 let spans = self
                     .move_spans(&Place::Base(PlaceBase::Local(local)), location)
@@ -323,6 +327,77 @@ impl<'cx, 'gcx, 'tcx> MirBorrowckCtxt<'cx, 'gcx, 'tcx> {
         }
     }
 
+    /// Reports, for each borrow, where the region of the reference it creates
+    /// first becomes live on the way from the start of the body, and the use
+    /// that keeps it live from there, as found by `find_use::find_with_live_start`.
+    /// This is only used to test that search, under
+    /// `#[rustc_mir(borrowck_live_start)]`. With `stop_at_call = "f"`, the
+    /// search is bounded at the first call to a function named `f`.
+    pub(in crate::borrow_check) fn report_borrow_live_starts(
+        &mut self,
+        stop_at_call: Option<Symbol>,
+    ) {
+        let tcx = self.infcx.tcx;
+        let stop_at = stop_at_call.and_then(|name| {
+            self.body.basic_blocks().iter_enumerated().find_map(|(block, data)| {
+                match data.terminator().kind {
+                    TerminatorKind::Call { func: Operand::Constant(ref func), .. } => {
+                        match func.ty.sty {
+                            ty::FnDef(def_id, _) if tcx.item_name(def_id) == name => {
+                                Some(self.body.terminator_loc(block))
+                            }
+                            _ => None,
+                        }
+                    }
+                    _ => None,
+                }
+            })
+        });
+        for borrow in self.borrow_set.borrows.iter() {
+            // Like `explain_why_borrow_contains_point`, look for the uses of a
+            // region the borrow region outlives, such as that of the type of the
+            // local the reference is assigned to.
+            let region_sub = self.nonlexical_regioncx.find_sub_region_live_at(
+                borrow.region,
+                borrow.reserve_location.successor_within_block(),
+            );
+            let (live_start, cause) = find_use::find_with_live_start(
+                self.body,
+                &self.nonlexical_regioncx,
+                &self.borrow_set,
+                tcx,
+                region_sub,
+                Location::START,
+                stop_at,
+            );
+            let live_start = match live_start {
+                Some(live_start) => live_start,
+                None => {
+                    let span = self.body.source_info(borrow.reserve_location).span;
+                    tcx.sess.struct_span_err(span, "rustc_mir: borrow never becomes live")
+                        .buffer(&mut self.errors_buffer);
+                    continue;
+                }
+            };
+
+            let span = self.body.source_info(live_start).span;
+            let mut err = tcx.sess.struct_span_err(span, "rustc_mir: borrow becomes live here");
+            match cause {
+                Some(Cause::LiveVar(_, location))
+                | Some(Cause::DropVar(_, location)) => {
+                    err.span_note(self.body.source_info(location).span, "and is used here");
+                }
+                Some(Cause::Activation(_, location)) => {
+                    err.span_note(self.body.source_info(location).span, "and is activated here");
+                }
+                None => {
+                    err.note("and is not used");
+                }
+            }
+            err.buffer(&mut self.errors_buffer);
+        }
+    }
+
     /// true if `borrow_location` can reach `use_location` by going through a loop and
     /// `use_location` is also inside of that loop
     fn is_use_in_later_iteration_of_loop(
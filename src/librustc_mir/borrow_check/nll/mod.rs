@@ -1,7 +1,9 @@
 use crate::borrow_check::borrow_set::BorrowSet;
 use crate::borrow_check::location::{LocationIndex, LocationTable};
 use crate::borrow_check::nll::facts::AllFactsExt;
-use crate::borrow_check::nll::type_check::{MirTypeckResults, MirTypeckRegionConstraints};
+use crate::borrow_check::nll::type_check::{
+    Locations, MirTypeckResults, MirTypeckRegionConstraints,
+};
 use crate::borrow_check::nll::region_infer::values::RegionValueElements;
 use crate::dataflow::indexes::BorrowIndex;
 use crate::dataflow::move_paths::MoveData;
@@ -114,6 +116,10 @@ pub(in crate::borrow_check) fn compute_regions<'cx, 'gcx, 'tcx>(
         elements,
     );
 
+    if infcx.tcx.sess.opts.debugging_opts.dump_nll_constraints {
+        dump_nll_constraints(infcx, def_id, &universal_regions, &constraints);
+    }
+
     if let Some(all_facts) = &mut all_facts {
         all_facts
             .universal_region
@@ -131,6 +137,8 @@ pub(in crate::borrow_check) fn compute_regions<'cx, 'gcx, 'tcx>(
         outlives_constraints,
         closure_bounds_mapping,
         type_tests,
+        query_constraints: _,
+        outlives_constraint_sources: _,
     } = constraints;
     let placeholder_indices = Rc::new(placeholder_indices);
 
@@ -210,6 +218,63 @@ pub(in crate::borrow_check) fn compute_regions<'cx, 'gcx, 'tcx>(
     (regioncx, polonius_output, closure_region_requirements)
 }
 
+/// Prints the outlives constraints and type tests produced by the MIR
+/// type-checker for `-Z dump-nll-constraints`. Both are sorted, so that the
+/// output does not depend on the order in which the constraints were added.
+/// Outlives constraints converted from a query region constraint are printed
+/// along with that query constraint, and type tests are grouped by the type
+/// they are about.
+fn dump_nll_constraints<'gcx, 'tcx>(
+    infcx: &InferCtxt<'_, 'gcx, 'tcx>,
+    def_id: DefId,
+    universal_regions: &UniversalRegions<'tcx>,
+    constraints: &MirTypeckRegionConstraints<'tcx>,
+) {
+    let mut outlives_constraints: Vec<_> = constraints
+        .outlives_constraints
+        .iter_enumerated()
+        .map(|(index, constraint)| (constraint, constraints.query_constraint_source(index)))
+        .collect();
+    outlives_constraints.sort_by(|a, b| a.0.cmp(b.0));
+
+    let mut generic_kinds = vec![];
+    for type_test in &constraints.type_tests {
+        if !generic_kinds.contains(&type_test.generic_kind) {
+            generic_kinds.push(type_test.generic_kind);
+        }
+    }
+    generic_kinds.sort_by_key(|generic_kind| generic_kind.to_string());
+
+    println!("dump-nll-constraints body: `{}`", infcx.tcx.def_path_str(def_id));
+    for (constraint, source) in outlives_constraints {
+        let locations = match constraint.locations {
+            Locations::All(_) => String::from("all"),
+            Locations::Single(location) => format!("{:?}", location),
+        };
+        let source = match source {
+            Some(query_constraint) => format!(" from {:?}", query_constraint),
+            None => String::new(),
+        };
+        println!(
+            "dump-nll-constraints     outlives: {:?}: {:?} (at {}) due to {:?}{}",
+            constraint.sup, constraint.sub, locations, constraint.category, source,
+        );
+    }
+    for generic_kind in &generic_kinds {
+        let mut type_tests: Vec<_> = constraints
+            .type_tests_for(generic_kind)
+            .map(|type_test| {
+                let regions = type_test.referenced_regions(universal_regions);
+                format!("{} mentions {:?}", type_test, regions)
+            })
+            .collect();
+        type_tests.sort();
+        for type_test in type_tests {
+            println!("dump-nll-constraints     type test: {}", type_test);
+        }
+    }
+}
+
 fn dump_mir_results<'a, 'gcx, 'tcx>(
     infcx: &InferCtxt<'a, 'gcx, 'tcx>,
     source: MirSource<'tcx>,
@@ -53,6 +53,12 @@ impl<'tcx> RegionInferenceContext<'tcx> {
         writeln!(out, "| Inference Constraints")?;
         self.for_each_constraint(&mut |msg| writeln!(out, "| {}", msg))?;
 
+        writeln!(out, "|")?;
+        writeln!(out, "| Type Tests")?;
+        for type_test in &self.type_tests {
+            writeln!(out, "| {}", type_test)?;
+        }
+
         Ok(())
     }
 
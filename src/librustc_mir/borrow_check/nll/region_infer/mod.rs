@@ -24,6 +24,7 @@ use rustc_data_structures::indexed_vec::IndexVec;
 use rustc_errors::{Diagnostic, DiagnosticBuilder};
 use syntax_pos::Span;
 
+use std::fmt;
 use std::rc::Rc;
 
 mod dump_mir;
@@ -122,6 +123,10 @@ pub(crate) enum Cause {
 
     /// point inserted because Local was dropped at the given Location
     DropVar(Local, Location),
+
+    /// point inserted because Local, the temporary holding a two-phase
+    /// borrow, was used at the given Location, which activates the borrow
+    Activation(Local, Location),
 }
 
 /// A "type test" corresponds to an outlives constraint between a type
@@ -172,6 +177,82 @@ pub struct TypeTest<'tcx> {
     pub verify_bound: VerifyBound<'tcx>,
 }
 
+impl<'tcx> TypeTest<'tcx> {
+    /// Returns every region this type test mentions: the `lower_bound` first, followed
+    /// by the regions of the `verify_bound` tree in the order they appear, without
+    /// duplicates. Only the bounds nested under an `IfEq` are walked, not the regions
+    /// appearing in the type it compares against. Free regions are mapped to their
+    /// variables using `universal_regions`.
+    pub fn referenced_regions(&self, universal_regions: &UniversalRegions<'tcx>) -> Vec<RegionVid> {
+        fn walk<'tcx>(
+            verify_bound: &VerifyBound<'tcx>,
+            universal_regions: &UniversalRegions<'tcx>,
+            regions: &mut Vec<RegionVid>,
+        ) {
+            match verify_bound {
+                VerifyBound::IfEq(_, verify_bound1) => {
+                    walk(verify_bound1, universal_regions, regions)
+                }
+                VerifyBound::OutlivedBy(r) => {
+                    let vid = universal_regions.to_region_vid(r);
+                    if !regions.contains(&vid) {
+                        regions.push(vid);
+                    }
+                }
+                VerifyBound::AnyBound(verify_bounds) | VerifyBound::AllBounds(verify_bounds) => {
+                    for verify_bound in verify_bounds {
+                        walk(verify_bound, universal_regions, regions);
+                    }
+                }
+            }
+        }
+
+        let mut regions = vec![self.lower_bound];
+        walk(&self.verify_bound, universal_regions, &mut regions);
+        regions
+    }
+}
+
+/// Renders a type test on one line for `-Zdump-mir=nll`, e.g.
+/// `T: '_#3r (at bb3[2]) verify { any('_#1r, '_#2r) }`. Only the
+/// outermost level of the verify bound is expanded.
+impl fmt::Display for TypeTest<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {:?}", self.generic_kind, self.lower_bound)?;
+        match self.locations {
+            Locations::All(_) => write!(f, " (at all)")?,
+            Locations::Single(location) => write!(f, " (at {:?})", location)?,
+        }
+        write!(f, " verify {{ {} }}", summarize_verify_bound(&self.verify_bound, true))
+    }
+}
+
+fn summarize_verify_bound(verify_bound: &VerifyBound<'_>, expand: bool) -> String {
+    let summarize_all = |verify_bounds: &[VerifyBound<'_>]| {
+        verify_bounds
+            .iter()
+            .map(|verify_bound| summarize_verify_bound(verify_bound, false))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    match verify_bound {
+        VerifyBound::OutlivedBy(r) => format!("{:?}", r),
+        VerifyBound::IfEq(test_ty, verify_bound1) if expand => {
+            format!("if_eq({:?}, {})", test_ty, summarize_verify_bound(verify_bound1, false))
+        }
+        VerifyBound::AnyBound(verify_bounds) if expand => {
+            format!("any({})", summarize_all(verify_bounds))
+        }
+        VerifyBound::AllBounds(verify_bounds) if expand => {
+            format!("all({})", summarize_all(verify_bounds))
+        }
+        VerifyBound::IfEq(..) => "if_eq(..)".to_string(),
+        VerifyBound::AnyBound(verify_bounds) => format!("any(<{} bounds>)", verify_bounds.len()),
+        VerifyBound::AllBounds(verify_bounds) => format!("all(<{} bounds>)", verify_bounds.len()),
+    }
+}
+
 impl<'tcx> RegionInferenceContext<'tcx> {
     /// Creates a new region inference context with a total of
     /// `num_region_variables` valid inference variables; the first N
@@ -1,4 +1,5 @@
-use crate::borrow_check::nll::constraints::OutlivesConstraint;
+use crate::borrow_check::nll::constraints::{OutlivesConstraint, QueryConstraintIndex};
+use crate::borrow_check::nll::facts::{AllFacts, AllFactsExt};
 use crate::borrow_check::nll::region_infer::TypeTest;
 use crate::borrow_check::nll::type_check::{Locations, MirTypeckRegionConstraints};
 use crate::borrow_check::nll::universal_regions::UniversalRegions;
@@ -23,6 +24,10 @@ crate struct ConstraintConversion<'a, 'gcx: 'tcx, 'tcx: 'a> {
     locations: Locations,
     category: ConstraintCategory,
     constraints: &'a mut MirTypeckRegionConstraints<'tcx>,
+
+    /// The query constraint currently being converted, if any; recorded
+    /// as the source of every outlives constraint we add.
+    query_constraint: Option<QueryConstraintIndex>,
 }
 
 impl<'a, 'gcx, 'tcx> ConstraintConversion<'a, 'gcx, 'tcx> {
@@ -46,6 +51,7 @@ impl<'a, 'gcx, 'tcx> ConstraintConversion<'a, 'gcx, 'tcx> {
             locations,
             category,
             constraints,
+            query_constraint: None,
         }
     }
 
@@ -58,6 +64,13 @@ impl<'a, 'gcx, 'tcx> ConstraintConversion<'a, 'gcx, 'tcx> {
     pub(super) fn convert(&mut self, query_constraint: &QueryRegionConstraint<'tcx>) {
         debug!("generate: constraints at: {:#?}", self.locations);
 
+        // Only `-Z dump-nll-constraints` looks at where the outlives
+        // constraints came from, so don't keep track of it otherwise.
+        if self.tcx.sess.opts.debugging_opts.dump_nll_constraints {
+            self.query_constraint =
+                Some(self.constraints.query_constraints.push(*query_constraint));
+        }
+
         // Extract out various useful fields we'll need below.
         let ConstraintConversion {
             tcx,
@@ -105,6 +118,8 @@ impl<'a, 'gcx, 'tcx> ConstraintConversion<'a, 'gcx, 'tcx> {
                 // don't need to handle any relations here.
             }
         }
+
+        self.query_constraint = None;
     }
 
     fn verify_to_type_test(
@@ -134,14 +149,28 @@ impl<'a, 'gcx, 'tcx> ConstraintConversion<'a, 'gcx, 'tcx> {
     }
 
     fn add_outlives(&mut self, sup: ty::RegionVid, sub: ty::RegionVid) {
-        self.constraints
+        // `'static: 'a` always holds, and the constraint graph already has
+        // implicit edges from `'static` to every region (see
+        // `ConstraintGraph::outgoing_edges`), which are the only ones it
+        // follows out of `'static`, so adding it would only be noise. The
+        // Polonius facts have no such implicit edges, so it is kept for them.
+        // (Reflexive `'a: 'a` constraints are dropped by `push`.)
+        if sup == self.universal_regions.fr_static && !AllFacts::enabled(self.tcx) {
+            debug!("add_outlives: dropping trivial constraint {:?}: {:?}", sup, sub);
+            return;
+        }
+
+        let index = self.constraints
             .outlives_constraints
-            .push(OutlivesConstraint {
+            .push_or_merge(OutlivesConstraint {
                 locations: self.locations,
                 category: self.category,
                 sub,
                 sup,
             });
+        if let (Some(index), Some(source)) = (index, self.query_constraint) {
+            self.constraints.outlives_constraint_sources.insert(index, source);
+        }
     }
 
     fn add_type_test(&mut self, type_test: TypeTest<'tcx>) {
@@ -4,7 +4,9 @@
 
 use crate::borrow_check::borrow_set::BorrowSet;
 use crate::borrow_check::location::LocationTable;
-use crate::borrow_check::nll::constraints::{ConstraintSet, OutlivesConstraint};
+use crate::borrow_check::nll::constraints::{
+    ConstraintIndex, ConstraintSet, OutlivesConstraint, QueryConstraintIndex,
+};
 use crate::borrow_check::nll::facts::AllFacts;
 use crate::borrow_check::nll::region_infer::values::LivenessValues;
 use crate::borrow_check::nll::region_infer::values::PlaceholderIndex;
@@ -25,6 +27,7 @@ use rustc::hir;
 use rustc::hir::def_id::DefId;
 use rustc::infer::canonical::QueryRegionConstraint;
 use rustc::infer::outlives::env::RegionBoundPairs;
+use rustc::infer::region_constraints::GenericKind;
 use rustc::infer::{InferCtxt, InferOk, LateBoundRegionConversionTime, NLLRegionVariableOrigin};
 use rustc::infer::type_variable::{TypeVariableOrigin, TypeVariableOriginKind};
 use rustc::mir::interpret::{InterpError::BoundsCheck, ConstValue};
@@ -130,6 +133,8 @@ pub(crate) fn type_check<'gcx, 'tcx>(
         outlives_constraints: ConstraintSet::default(),
         closure_bounds_mapping: Default::default(),
         type_tests: Vec::default(),
+        query_constraints: IndexVec::default(),
+        outlives_constraint_sources: Default::default(),
     };
 
     let CreateResult {
@@ -593,7 +598,10 @@ impl<'a, 'b, 'gcx, 'tcx> TypeVerifier<'a, 'b, 'gcx, 'tcx> {
                 // temporary from the user's point of view.
                 constraint.category = ConstraintCategory::Boring;
             }
-            self.cx.borrowck_context.constraints.outlives_constraints.push(constraint)
+            // Every constraint from the promoted now holds at the single
+            // location where it is used, so constraints between the same
+            // regions collapse into one.
+            self.cx.borrowck_context.constraints.outlives_constraints.push_or_merge(constraint);
         }
 
         if !closure_bounds.is_empty() {
@@ -890,6 +898,17 @@ crate struct MirTypeckRegionConstraints<'tcx> {
         FxHashMap<Location, FxHashMap<(RegionVid, RegionVid), (ConstraintCategory, Span)>>,
 
     crate type_tests: Vec<TypeTest<'tcx>>,
+
+    /// Every query region constraint that has been converted into
+    /// outlives constraints, in the order they were converted. Only
+    /// recorded with `-Z dump-nll-constraints`.
+    crate query_constraints: IndexVec<QueryConstraintIndex, QueryRegionConstraint<'tcx>>,
+
+    /// For the outlives constraints that came from converting a query
+    /// region constraint, the index of that query constraint. This
+    /// traces a constraint back to the obligation that produced it.
+    /// Only recorded with `-Z dump-nll-constraints`.
+    crate outlives_constraint_sources: FxHashMap<ConstraintIndex, QueryConstraintIndex>,
 }
 
 impl MirTypeckRegionConstraints<'tcx> {
@@ -909,6 +928,25 @@ impl MirTypeckRegionConstraints<'tcx> {
             }
         }
     }
+
+    /// Returns the query region constraint that produced the outlives
+    /// constraint `index`, if it was produced by one.
+    crate fn query_constraint_source(
+        &self,
+        index: ConstraintIndex,
+    ) -> Option<&QueryRegionConstraint<'tcx>> {
+        self.outlives_constraint_sources
+            .get(&index)
+            .map(|&source| &self.query_constraints[source])
+    }
+
+    /// Returns the type tests whose subject is `kind`.
+    crate fn type_tests_for<'a>(
+        &'a self,
+        kind: &'a GenericKind<'tcx>,
+    ) -> impl Iterator<Item = &'a TypeTest<'tcx>> + 'a {
+        self.type_tests.iter().filter(move |type_test| type_test.generic_kind == *kind)
+    }
 }
 
 /// The `Locations` type summarizes *where* region constraints are
@@ -98,7 +98,7 @@ impl TypeRelatingDelegate<'tcx> for NllTypeRelatingDelegate<'_, '_, '_, 'tcx> {
             borrowck_context
                 .constraints
                 .outlives_constraints
-                .push(OutlivesConstraint {
+                .push_or_merge(OutlivesConstraint {
                     sup,
                     sub,
                     locations: self.locations,
@@ -3,7 +3,7 @@ use crate::borrow_check::places_conflict;
 use crate::borrow_check::AccessDepth;
 use crate::dataflow::indexes::BorrowIndex;
 use rustc::mir::{BasicBlock, Location, Body, Place, PlaceBase};
-use rustc::mir::{ProjectionElem, BorrowKind};
+use rustc::mir::BorrowKind;
 use rustc::ty::TyCtxt;
 use rustc_data_structures::graph::dominators::Dominators;
 
@@ -40,17 +40,22 @@ pub(super) fn each_borrow_involving_path<'tcx, 'gcx: 'tcx, F, I, S>(
     // FIXME: analogous code in check_loans first maps `place` to
     // its base_path.
 
+    // What the conflict walk finds out about the prefixes of `place` is shared
+    // by all the candidates, so each prefix is only looked at once.
+    let mut access_prefix_tys = places_conflict::AccessPrefixTys::new();
+
     // check for loan restricting path P being used. Accounts for
     // borrows of P, P.a.b, etc.
     for i in candidates {
         let borrowed = &borrow_set[i];
 
-        if places_conflict::borrow_conflicts_with_place(
+        if places_conflict::borrow_conflicts_with_access(
             tcx,
             body,
             &borrowed.borrowed_place,
             borrowed.kind,
             place,
+            &mut access_prefix_tys,
             access,
             places_conflict::PlaceConflictBias::Overlap,
         ) {
@@ -131,20 +136,14 @@ pub(super) fn is_active<'tcx>(
 /// Determines if a given borrow is borrowing local data
 /// This is called for all Yield statements on movable generators
 pub(super) fn borrow_of_local_data<'tcx>(place: &Place<'tcx>) -> bool {
-    place.iterate(|place_base, place_projection| {
-        match place_base {
-            PlaceBase::Static(..) => return false,
-            PlaceBase::Local(..) => {},
-        }
-
-        for proj in place_projection {
-            // Reborrow of already borrowed data is ignored
-            // Any errors will be caught on the initial borrow
-            if proj.elem == ProjectionElem::Deref {
-                return false;
-            }
-        }
+    // Reborrow of already borrowed data is ignored
+    // Any errors will be caught on the initial borrow
+    if places_conflict::first_deref_base(place).is_some() {
+        return false;
+    }
 
-        true
+    place.iterate(|place_base, _| match place_base {
+        PlaceBase::Static(..) => false,
+        PlaceBase::Local(..) => true,
     })
 }
@@ -3,11 +3,15 @@ use crate::borrow_check::Overlap;
 use crate::borrow_check::{Deep, Shallow, AccessDepth};
 use rustc::hir;
 use rustc::mir::{
-    BorrowKind, Body, Place, PlaceBase, Projection, ProjectionElem, ProjectionsIter,
-    StaticKind
+    BorrowKind, Body, Field, Place, PlaceBase, PlaceElem, Projection, ProjectionElem,
+    ProjectionsIter, StaticKind
 };
-use rustc::ty::{self, TyCtxt};
+use rustc::mir::tcx::PlaceTy;
+use rustc::ty::{self, Ty, TyCtxt};
+use rustc::util::nodemap::FxHashMap;
+use smallvec::SmallVec;
 use std::cmp::max;
+use std::sync::atomic::Ordering;
 
 /// When checking if a place conflicts with another place, this enum is used to influence decisions
 /// where a place might be equal or disjoint with another place, such as if `a[i] == a[j]`.
@@ -42,6 +46,20 @@ crate fn places_conflict<'gcx, 'tcx>(
     )
 }
 
+/// Returns `true` only if `a` and `b` are proven to never refer to overlapping memory, e.g.,
+/// `x.0` and `x.1`. Anything the conflict analysis cannot decide counts as overlapping: for
+/// instance `a[i]` and `a[j]` are *not* definitely disjoint, as `i` may equal `j`. This makes
+/// it the predicate to use when a transformation is only sound for disjoint places (such as
+/// reordering a load and a store), rather than picking a `PlaceConflictBias` by hand.
+crate fn places_definitely_disjoint<'gcx, 'tcx>(
+    tcx: TyCtxt<'gcx, 'tcx>,
+    body: &Body<'tcx>,
+    a: &Place<'tcx>,
+    b: &Place<'tcx>,
+) -> bool {
+    !places_conflict(tcx, body, a, b, PlaceConflictBias::Overlap)
+}
+
 /// Checks whether the `borrow_place` conflicts with the `access_place` given a borrow kind and
 /// access depth. The `bias` parameter is used to determine how the unknowable (comparing runtime
 /// array indices, for example) should be interpreted - this depends on what the caller wants in
@@ -55,6 +73,187 @@ pub(super) fn borrow_conflicts_with_place<'gcx, 'tcx>(
     access: AccessDepth,
     bias: PlaceConflictBias,
 ) -> bool {
+    explain_borrow_conflict_with_place(
+        tcx,
+        body,
+        borrow_place,
+        borrow_kind,
+        access_place,
+        access,
+        bias,
+        None,
+    ).is_conflict()
+}
+
+/// Whether the prefixes of an accessed place are unions, from its base up,
+/// computed as the conflict walk gets to them.
+///
+/// While the walk compares a borrowed place and an accessed place that are equal
+/// or disjoint so far, the bases of the projections it compares have the same
+/// type, so whether that is a union can be taken from the access. When checking
+/// one access against many borrows, pass the same `AccessPrefixTys` to
+/// `borrow_conflicts_with_access` for all of them, so that each prefix of the
+/// access is only looked at once.
+crate struct AccessPrefixTys<'tcx> {
+    /// The union definition of the prefix with `depth` projections, if it is a
+    /// union, for the prefixes the walk got to so far.
+    union_defs: SmallVec<[Option<&'tcx ty::AdtDef>; 8]>,
+    /// The type of the last prefix in `union_defs`.
+    last_ty: Option<PlaceTy<'tcx>>,
+}
+
+impl AccessPrefixTys<'tcx> {
+    crate fn new() -> Self {
+        AccessPrefixTys { union_defs: SmallVec::new(), last_ty: None }
+    }
+
+    /// Returns the definition of the type of `access_c.base` if it is a union,
+    /// where `access_c` is the projection of the access at `depth`. The walk
+    /// gets to each depth after the ones before it, so when `depth` is looked
+    /// at for the first time, `last_ty` is the type of the base of `access_c.base`.
+    fn base_union_def(
+        &mut self,
+        tcx: TyCtxt<'_, 'tcx>,
+        body: &Body<'tcx>,
+        access_c: &Projection<'tcx>,
+        depth: usize,
+    ) -> Option<&'tcx ty::AdtDef> {
+        if let Some(&union_def) = self.union_defs.get(depth) {
+            return union_def;
+        }
+        debug_assert_eq!(depth, self.union_defs.len());
+        let base_ty = match (&access_c.base, self.last_ty) {
+            (Place::Projection(box base), Some(last_ty)) => last_ty.projection_ty(tcx, &base.elem),
+            (base, _) => base.ty(body, tcx),
+        };
+        let base_union = union_def(base_ty.ty);
+        self.union_defs.push(base_union);
+        self.last_ty = Some(base_ty);
+        base_union
+    }
+}
+
+/// Like `borrow_conflicts_with_place`, but sharing `access_prefix_tys` with the
+/// other checks of `access_place`.
+pub(super) fn borrow_conflicts_with_access<'gcx, 'tcx>(
+    tcx: TyCtxt<'gcx, 'tcx>,
+    body: &Body<'tcx>,
+    borrow_place: &Place<'tcx>,
+    borrow_kind: BorrowKind,
+    access_place: &Place<'tcx>,
+    access_prefix_tys: &mut AccessPrefixTys<'tcx>,
+    access: AccessDepth,
+    bias: PlaceConflictBias,
+) -> bool {
+    explain_borrow_conflict_with_place(
+        tcx,
+        body,
+        borrow_place,
+        borrow_kind,
+        access_place,
+        access,
+        bias,
+        Some(access_prefix_tys),
+    ).is_conflict()
+}
+
+/// Borrowed places and the kinds of their borrows, grouped by the base of the
+/// borrowed place, as built by `group_borrows_by_root`.
+crate type BorrowsByRoot<'tcx> = FxHashMap<PlaceBase<'tcx>, Vec<(Place<'tcx>, BorrowKind)>>;
+
+/// Groups `borrows` by the base of the borrowed place, for `any_conflict`.
+crate fn group_borrows_by_root<'a, 'tcx: 'a>(
+    borrows: impl IntoIterator<Item = (&'a Place<'tcx>, BorrowKind)>,
+) -> BorrowsByRoot<'tcx> {
+    let mut by_root = BorrowsByRoot::default();
+    for (place, kind) in borrows {
+        let root = place.iterate(|base, _| base.clone());
+        by_root.entry(root).or_insert_with(Vec::new).push((place.clone(), kind));
+    }
+    by_root
+}
+
+/// Checks whether `access_place` conflicts with any of `borrows_by_root`, like
+/// calling `borrow_conflicts_with_place` for each of them. Places with different
+/// bases never conflict, so only the borrows sharing the base of `access_place`
+/// are compared, and the rest are skipped without walking their projections.
+crate fn any_conflict<'gcx, 'tcx>(
+    tcx: TyCtxt<'gcx, 'tcx>,
+    body: &Body<'tcx>,
+    access_place: &Place<'tcx>,
+    access: AccessDepth,
+    borrows_by_root: &BorrowsByRoot<'tcx>,
+    bias: PlaceConflictBias,
+) -> bool {
+    access_place.iterate(|access_base, _| {
+        let borrows = match borrows_by_root.get(access_base) {
+            Some(borrows) => borrows,
+            None => return false,
+        };
+        // Even equal bases can be known to be disjoint, e.g., for `static mut`s.
+        if let Overlap::Disjoint = place_base_conflict(tcx, access_base, access_base) {
+            return false;
+        }
+        let mut access_prefix_tys = AccessPrefixTys::new();
+        borrows.iter().any(|(borrow_place, borrow_kind)| {
+            borrow_conflicts_with_access(
+                tcx,
+                body,
+                borrow_place,
+                *borrow_kind,
+                access_place,
+                &mut access_prefix_tys,
+                access,
+                bias,
+            )
+        })
+    })
+}
+
+/// The step of `borrow_conflicts_with_place` that decided whether two places conflict.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+crate enum PlaceConflictExplanation {
+    /// The places were proven disjoint by the components at the given depth, where
+    /// depth 0 is the base of the places and depth `n` their `n`th projection.
+    DisjointAt(usize),
+    /// The borrowed place extends past the accessed place, but only into data the
+    /// access does not touch, e.g., a borrow of `*x.y` and a shallow access of `x`.
+    DisjointPastAccess,
+    /// A shallow borrow of a prefix of the accessed place.
+    DisjointShallowBorrow,
+    /// The places are the same.
+    ConflictEqual,
+    /// One of the places is a prefix of the other.
+    ConflictPrefix,
+    /// The places go through different fields of the same union.
+    ConflictUnionArbitrary,
+}
+
+impl PlaceConflictExplanation {
+    crate fn is_conflict(self) -> bool {
+        match self {
+            PlaceConflictExplanation::DisjointAt(_)
+            | PlaceConflictExplanation::DisjointPastAccess
+            | PlaceConflictExplanation::DisjointShallowBorrow => false,
+            PlaceConflictExplanation::ConflictEqual
+            | PlaceConflictExplanation::ConflictPrefix
+            | PlaceConflictExplanation::ConflictUnionArbitrary => true,
+        }
+    }
+}
+
+/// Like `borrow_conflicts_with_place`, but returns which step decided the outcome.
+/// The prefix types of `access_place` are computed here if not given.
+crate fn explain_borrow_conflict_with_place<'gcx, 'tcx>(
+    tcx: TyCtxt<'gcx, 'tcx>,
+    body: &Body<'tcx>,
+    borrow_place: &Place<'tcx>,
+    borrow_kind: BorrowKind,
+    access_place: &Place<'tcx>,
+    access: AccessDepth,
+    bias: PlaceConflictBias,
+    access_prefix_tys: Option<&mut AccessPrefixTys<'tcx>>,
+) -> PlaceConflictExplanation {
     debug!(
         "borrow_conflicts_with_place({:?}, {:?}, {:?}, {:?})",
         borrow_place, access_place, access, bias,
@@ -64,10 +263,45 @@ pub(super) fn borrow_conflicts_with_place<'gcx, 'tcx>(
     // it's so common that it's a speed win to check for it first.
     if let Place::Base(PlaceBase::Local(l1)) = borrow_place {
         if let Place::Base(PlaceBase::Local(l2)) = access_place {
-            return l1 == l2;
+            return if l1 == l2 {
+                PlaceConflictExplanation::ConflictEqual
+            } else {
+                PlaceConflictExplanation::DisjointAt(0)
+            };
         }
     }
 
+    // Likewise, the same static accessed through the same projections is
+    // common enough in code using statics to be worth a quick check.
+    if let Some(explanation) = equal_static_places_conflict(tcx, borrow_place, access_place) {
+        return explanation;
+    }
+
+    explain_borrow_conflict_by_walk(
+        tcx,
+        body,
+        borrow_place,
+        borrow_kind,
+        access_place,
+        access,
+        bias,
+        access_prefix_tys,
+    )
+}
+
+/// Like `explain_borrow_conflict_with_place`, but always walks the projections of
+/// the two places in lockstep, without first trying the fast paths for common
+/// cases. The fast paths must agree with this.
+crate fn explain_borrow_conflict_by_walk<'gcx, 'tcx>(
+    tcx: TyCtxt<'gcx, 'tcx>,
+    body: &Body<'tcx>,
+    borrow_place: &Place<'tcx>,
+    borrow_kind: BorrowKind,
+    access_place: &Place<'tcx>,
+    access: AccessDepth,
+    bias: PlaceConflictBias,
+    access_prefix_tys: Option<&mut AccessPrefixTys<'tcx>>,
+) -> PlaceConflictExplanation {
     borrow_place.iterate(|borrow_base, borrow_projections| {
         access_place.iterate(|access_base, access_projections| {
             place_components_conflict(
@@ -76,6 +310,7 @@ pub(super) fn borrow_conflicts_with_place<'gcx, 'tcx>(
                 (borrow_base, borrow_projections),
                 borrow_kind,
                 (access_base, access_projections),
+                access_prefix_tys,
                 access,
                 bias,
             )
@@ -83,15 +318,135 @@ pub(super) fn borrow_conflicts_with_place<'gcx, 'tcx>(
     })
 }
 
+/// Two places which conflict because they access different fields of the
+/// same union (see `Overlap::Arbitrary`).
+crate struct UnionFieldConflict<'tcx> {
+    /// The union place both places are projected from, e.g., `u` for
+    /// `u.a.x` and `u.b`.
+    crate union_place: Place<'tcx>,
+    crate union_ty: Ty<'tcx>,
+    /// The fields of the union accessed by the first and second place.
+    crate fields: (Field, Field),
+}
+
+/// Walks `first_place` and `second_place` in lockstep (like `borrow_conflicts_with_place`)
+/// and, if they are found to overlap because they go through different fields of the same
+/// union, returns that union and the two fields.
+crate fn union_field_conflict<'gcx, 'tcx>(
+    tcx: TyCtxt<'gcx, 'tcx>,
+    body: &Body<'tcx>,
+    first_place: &Place<'tcx>,
+    second_place: &Place<'tcx>,
+) -> Option<UnionFieldConflict<'tcx>> {
+    first_place.iterate(|first_base, first_projections| {
+        second_place.iterate(|second_base, second_projections| {
+            match place_base_conflict(tcx, first_base, second_base) {
+                Overlap::EqualOrDisjoint => {}
+                Overlap::Arbitrary { .. } | Overlap::Disjoint => return None,
+            }
+
+            let mut first_base_ty = place_base_ty(body, first_base);
+            for (first_c, second_c) in first_projections.zip(second_projections) {
+                let bias = PlaceConflictBias::Overlap;
+                let base_union = union_def(first_base_ty.ty);
+                match place_projection_conflict(first_c, second_c, base_union, bias) {
+                    Overlap::Arbitrary { fields, .. } => {
+                        return Some(UnionFieldConflict {
+                            union_place: first_c.base.clone(),
+                            union_ty: first_base_ty.ty,
+                            fields,
+                        });
+                    }
+                    Overlap::EqualOrDisjoint => {}
+                    Overlap::Disjoint => return None,
+                }
+                first_base_ty = first_base_ty.projection_ty(tcx, &first_c.elem);
+            }
+
+            None
+        })
+    })
+}
+
+/// Strips trailing field projections through `#[repr(transparent)]` structs with a
+/// single field. Such a field occupies exactly the same memory as its parent, so for
+/// e.g. `x: Wrapper<T>` a conflict on `x.0` is really a conflict on `x`, and
+/// diagnostics read better when they mention the outer place.
+crate fn strip_transparent_fields<'a, 'gcx, 'tcx>(
+    tcx: TyCtxt<'gcx, 'tcx>,
+    body: &Body<'tcx>,
+    mut place: &'a Place<'tcx>,
+) -> &'a Place<'tcx> {
+    while let Place::Projection(box Projection { base, elem: ProjectionElem::Field(..) }) = place {
+        match base.ty(body, tcx).ty.sty {
+            ty::Adt(def, _) if def.is_struct()
+                && def.repr.transparent()
+                && def.non_enum_variant().fields.len() == 1 => {
+                debug!("strip_transparent_fields: {:?} -> {:?}", place, base);
+                place = base;
+            }
+            _ => break,
+        }
+    }
+    place
+}
+
+/// Strips the downcast to an enum variant from the end of `place`, so that
+/// diagnostics can refer to the enum itself: returns `x` for both `(x as V)`
+/// and a field of the variant, `(x as V).f`. Any other place is returned as is.
+crate fn strip_downcast<'a, 'tcx>(place: &'a Place<'tcx>) -> &'a Place<'tcx> {
+    let variant_place = match place {
+        Place::Projection(box Projection { base, elem: ProjectionElem::Field(..) }) => base,
+        _ => place,
+    };
+    match variant_place {
+        Place::Projection(box Projection { base, elem: ProjectionElem::Downcast(..) }) => base,
+        _ => place,
+    }
+}
+
+/// If `place` goes through a pointer, returns the place that is dereferenced
+/// first, e.g., `x` for both `*x` and `(*x).y`, and `x.y` for `**x.y`. The
+/// returned place never involves a `Deref` itself. Returns `None` if `place`
+/// contains no `Deref` projection at all.
+crate fn first_deref_base<'a, 'tcx>(place: &'a Place<'tcx>) -> Option<&'a Place<'tcx>> {
+    let mut deref_base = None;
+    let mut cursor = place;
+    while let Place::Projection(box Projection { base, elem }) = cursor {
+        if let ProjectionElem::Deref = elem {
+            deref_base = Some(base);
+        }
+        cursor = base;
+    }
+    deref_base
+}
+
+/// Returns `true` if `access_place` reborrows the referent of `borrow_place`,
+/// i.e., if it is `*borrow_place` or a projection of it, like `(*x).f` for
+/// the borrow `x`. Accessing `x` itself, or `x.f`, is not a reborrow of `x`.
+crate fn is_reborrow_of<'tcx>(borrow_place: &Place<'tcx>, access_place: &Place<'tcx>) -> bool {
+    let mut cursor = access_place;
+    while let Place::Projection(box Projection { base, elem }) = cursor {
+        if let ProjectionElem::Deref = elem {
+            if base == borrow_place {
+                return true;
+            }
+        }
+        cursor = base;
+    }
+    false
+}
+
 fn place_components_conflict<'gcx, 'tcx>(
     tcx: TyCtxt<'gcx, 'tcx>,
     body: &Body<'tcx>,
     borrow_projections: (&PlaceBase<'tcx>, ProjectionsIter<'_, 'tcx>),
     borrow_kind: BorrowKind,
     access_projections: (&PlaceBase<'tcx>, ProjectionsIter<'_, 'tcx>),
+    access_prefix_tys: Option<&mut AccessPrefixTys<'tcx>>,
     access: AccessDepth,
     bias: PlaceConflictBias,
-) -> bool {
+) -> PlaceConflictExplanation {
     // The borrowck rules for proving disjointness are applied from the "root" of the
     // borrow forwards, iterating over "similar" projections in lockstep until
     // we can prove overlap one way or another. Essentially, we treat `Overlap` as
@@ -138,7 +493,7 @@ fn place_components_conflict<'gcx, 'tcx>(
     let access_base = access_projections.0;
 
     match place_base_conflict(tcx, borrow_base, access_base) {
-        Overlap::Arbitrary => {
+        Overlap::Arbitrary { .. } => {
             bug!("Two base can't return Arbitrary");
         }
         Overlap::EqualOrDisjoint => {
@@ -148,12 +503,23 @@ fn place_components_conflict<'gcx, 'tcx>(
             // We have proven the borrow disjoint - further
             // projections will remain disjoint.
             debug!("borrow_conflicts_with_place: disjoint");
-            return false;
+            return PlaceConflictExplanation::DisjointAt(0);
         }
     }
 
     let mut borrow_projections = borrow_projections.1;
     let mut access_projections = access_projections.1;
+    let mut depth = 0;
+    let mut access_exhausted = false;
+
+    let mut own_prefix_tys;
+    let access_prefix_tys = match access_prefix_tys {
+        Some(access_prefix_tys) => access_prefix_tys,
+        None => {
+            own_prefix_tys = AccessPrefixTys::new();
+            &mut own_prefix_tys
+        }
+    };
 
     loop {
         // loop invariant: borrow_c is always either equal to access_c or disjoint from it.
@@ -162,6 +528,8 @@ fn place_components_conflict<'gcx, 'tcx>(
 
             if let Some(access_c) = access_projections.next() {
                 debug!("borrow_conflicts_with_place: access_c = {:?}", access_c);
+                let base_union = access_prefix_tys.base_union_def(tcx, body, access_c, depth);
+                depth += 1;
 
                 // Borrow and access path both have more components.
                 //
@@ -175,8 +543,12 @@ fn place_components_conflict<'gcx, 'tcx>(
                 // check whether the components being borrowed vs
                 // accessed are disjoint (as in the second example,
                 // but not the first).
-                match place_projection_conflict(tcx, body, borrow_c, access_c, bias) {
-                    Overlap::Arbitrary => {
+                let overlap = place_projection_conflict(borrow_c, access_c, base_union, bias);
+                if tcx.sess.opts.debugging_opts.place_conflict_stats {
+                    record_projection_pair(tcx, &borrow_c.elem, &access_c.elem, &overlap);
+                }
+                match overlap {
+                    Overlap::Arbitrary { union_def, fields } => {
                         // We have encountered different fields of potentially
                         // the same union - the borrow now partially overlaps.
                         //
@@ -190,8 +562,11 @@ fn place_components_conflict<'gcx, 'tcx>(
                         // idea, at least for now, so just give up and
                         // report a conflict. This is unsafe code anyway so
                         // the user could always use raw pointers.
-                        debug!("borrow_conflicts_with_place: arbitrary -> conflict");
-                        return true;
+                        debug!(
+                            "borrow_conflicts_with_place: arbitrary ({:?}: {:?}) -> conflict",
+                            union_def, fields,
+                        );
+                        return PlaceConflictExplanation::ConflictUnionArbitrary;
                     }
                     Overlap::EqualOrDisjoint => {
                         // This is the recursive case - proceed to the next element.
@@ -200,10 +575,15 @@ fn place_components_conflict<'gcx, 'tcx>(
                         // We have proven the borrow disjoint - further
                         // projections will remain disjoint.
                         debug!("borrow_conflicts_with_place: disjoint");
-                        return false;
+                        return PlaceConflictExplanation::DisjointAt(depth);
                     }
                 }
             } else {
+                access_exhausted = true;
+
+                let base = &borrow_c.base;
+                let base_ty = base.ty(body, tcx).ty;
+
                 // Borrow path is longer than the access path. Examples:
                 //
                 // - borrow of `a.b.c`, access to `a.b`
@@ -212,9 +592,7 @@ fn place_components_conflict<'gcx, 'tcx>(
                 // our place. This is a conflict if that is a part our
                 // access cares about.
 
-                let base = &borrow_c.base;
                 let elem = &borrow_c.elem;
-                let base_ty = base.ty(body, tcx).ty;
 
                 match (elem, &base_ty.sty, access) {
                     (_, _, Shallow(Some(ArtificialField::ArrayLength)))
@@ -227,7 +605,7 @@ fn place_components_conflict<'gcx, 'tcx>(
                         // e.g., a (mutable) borrow of `a[5]` while we read the
                         // array length of `a`.
                         debug!("borrow_conflicts_with_place: implicit field");
-                        return false;
+                        return PlaceConflictExplanation::DisjointPastAccess;
                     }
 
                     (ProjectionElem::Deref, _, Shallow(None)) => {
@@ -235,7 +613,7 @@ fn place_components_conflict<'gcx, 'tcx>(
                         // prefix thereof - the shallow access can't touch anything behind
                         // the pointer.
                         debug!("borrow_conflicts_with_place: shallow access behind ptr");
-                        return false;
+                        return PlaceConflictExplanation::DisjointPastAccess;
                     }
                     (ProjectionElem::Deref, ty::Ref(_, _, hir::MutImmutable), _) => {
                         // Shouldn't be tracked
@@ -245,14 +623,14 @@ fn place_components_conflict<'gcx, 'tcx>(
                         // Values behind a mutable reference are not access either by dropping a
                         // value, or by StorageDead
                         debug!("borrow_conflicts_with_place: drop access behind ptr");
-                        return false;
+                        return PlaceConflictExplanation::DisjointPastAccess;
                     }
 
                     (ProjectionElem::Field { .. }, ty::Adt(def, _), AccessDepth::Drop) => {
                         // Drop can read/write arbitrary projections, so places
                         // conflict regardless of further projections.
                         if def.has_dtor(tcx) {
-                            return true;
+                            return PlaceConflictExplanation::ConflictPrefix;
                         }
                     }
 
@@ -284,17 +662,60 @@ fn place_components_conflict<'gcx, 'tcx>(
             // If the second example, where we did, then we still know
             // that the borrow can access a *part* of our place that
             // our access cares about, so we still have a conflict.
-            if borrow_kind == BorrowKind::Shallow && access_projections.next().is_some() {
+            let access_is_longer = !access_exhausted && access_projections.next().is_some();
+            if borrow_kind == BorrowKind::Shallow && access_is_longer {
                 debug!("borrow_conflicts_with_place: shallow borrow");
-                return false;
+                return PlaceConflictExplanation::DisjointShallowBorrow;
+            } else if access_exhausted || access_is_longer {
+                debug!("borrow_conflicts_with_place: full borrow, CONFLICT");
+                return PlaceConflictExplanation::ConflictPrefix;
             } else {
                 debug!("borrow_conflicts_with_place: full borrow, CONFLICT");
-                return true;
+                return PlaceConflictExplanation::ConflictEqual;
             }
         }
     }
 }
 
+/// Returns `Some(ConflictEqual)` if both places are rooted at the same static
+/// and have structurally equal projections, and `None` if the general walk in
+/// `place_components_conflict` is needed to decide. Places with an `Index`
+/// projection are left to the general walk, as whether they conflict depends on
+/// the bias.
+crate fn equal_static_places_conflict<'gcx, 'tcx>(
+    tcx: TyCtxt<'gcx, 'tcx>,
+    borrow_place: &Place<'tcx>,
+    access_place: &Place<'tcx>,
+) -> Option<PlaceConflictExplanation> {
+    borrow_place.iterate(|borrow_base, mut borrow_projections| {
+        access_place.iterate(|access_base, mut access_projections| {
+            match (borrow_base, access_base) {
+                (PlaceBase::Static(_), PlaceBase::Static(_)) => {}
+                _ => return None,
+            }
+            match place_base_conflict(tcx, borrow_base, access_base) {
+                Overlap::EqualOrDisjoint => {}
+                _ => return None,
+            }
+
+            loop {
+                match (borrow_projections.next(), access_projections.next()) {
+                    (None, None) => {
+                        debug!("borrow_conflicts_with_place: equal static places, CONFLICT");
+                        return Some(PlaceConflictExplanation::ConflictEqual);
+                    }
+                    (Some(borrow_c), Some(access_c)) if borrow_c.elem == access_c.elem => {
+                        if let ProjectionElem::Index(_) = borrow_c.elem {
+                            return None;
+                        }
+                    }
+                    _ => return None,
+                }
+            }
+        })
+    })
+}
+
 // Given that the bases of `elem1` and `elem2` are always either equal
 // or disjoint (and have the same type!), return the overlap situation
 // between `elem1` and `elem2`.
@@ -302,7 +723,7 @@ fn place_base_conflict<'gcx: 'tcx, 'tcx>(
     tcx: TyCtxt<'gcx, 'tcx>,
     elem1: &PlaceBase<'tcx>,
     elem2: &PlaceBase<'tcx>,
-) -> Overlap {
+) -> Overlap<'tcx> {
     match (elem1, elem2) {
         (PlaceBase::Local(l1), PlaceBase::Local(l2)) => {
             if l1 == l2 {
@@ -362,16 +783,58 @@ fn place_base_conflict<'gcx: 'tcx, 'tcx>(
     }
 }
 
+/// Counts the comparison of `elem1` and `elem2` for `-Z place-conflict-stats`.
+fn record_projection_pair<'gcx, 'tcx>(
+    tcx: TyCtxt<'gcx, 'tcx>,
+    elem1: &PlaceElem<'tcx>,
+    elem2: &PlaceElem<'tcx>,
+    overlap: &Overlap<'tcx>,
+) {
+    let kind = |elem: &PlaceElem<'tcx>| match elem {
+        ProjectionElem::Deref => "Deref",
+        ProjectionElem::Field(..) => "Field",
+        ProjectionElem::Index(..) => "Index",
+        ProjectionElem::ConstantIndex { .. } => "ConstantIndex",
+        ProjectionElem::Subslice { .. } => "Subslice",
+        ProjectionElem::Downcast(..) => "Downcast",
+    };
+
+    let perf_stats = &tcx.sess.perf_stats;
+    *perf_stats.place_conflict_projection_pairs
+        .lock()
+        .entry((kind(elem1), kind(elem2)))
+        .or_insert(0) += 1;
+    if let Overlap::Arbitrary { .. } = overlap {
+        perf_stats.place_conflict_union_fields.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Returns the type of `base`, as the starting point for walking its projections.
+fn place_base_ty<'tcx>(body: &Body<'tcx>, base: &PlaceBase<'tcx>) -> PlaceTy<'tcx> {
+    match base {
+        PlaceBase::Local(local) => PlaceTy::from_ty(body.local_decls[*local].ty),
+        PlaceBase::Static(data) => PlaceTy::from_ty(data.ty),
+    }
+}
+
+/// Returns the definition of `ty` if it is a union.
+fn union_def<'tcx>(ty: Ty<'tcx>) -> Option<&'tcx ty::AdtDef> {
+    match ty.sty {
+        ty::Adt(def, _) if def.is_union() => Some(def),
+        _ => None,
+    }
+}
+
 // Given that the bases of `elem1` and `elem2` are always either equal
 // or disjoint (and have the same type!), return the overlap situation
-// between `elem1` and `elem2`.
-fn place_projection_conflict<'gcx: 'tcx, 'tcx>(
-    tcx: TyCtxt<'gcx, 'tcx>,
-    body: &Body<'tcx>,
+// between `elem1` and `elem2`. `base_union` is the definition of that
+// common base type, if it is a union.
+fn place_projection_conflict<'tcx>(
     pi1: &Projection<'tcx>,
     pi2: &Projection<'tcx>,
+    base_union: Option<&'tcx ty::AdtDef>,
     bias: PlaceConflictBias,
-) -> Overlap {
+) -> Overlap<'tcx> {
     match (&pi1.elem, &pi2.elem) {
         (ProjectionElem::Deref, ProjectionElem::Deref) => {
             // derefs (e.g., `*x` vs. `*x`) - recur.
@@ -384,14 +847,13 @@ fn place_projection_conflict<'gcx: 'tcx, 'tcx>(
                 debug!("place_element_conflict: DISJOINT-OR-EQ-FIELD");
                 Overlap::EqualOrDisjoint
             } else {
-                let ty = pi1.base.ty(body, tcx).ty;
-                match ty.sty {
-                    ty::Adt(def, _) if def.is_union() => {
+                match base_union {
+                    Some(def) => {
                         // Different fields of a union, we are basically stuck.
                         debug!("place_element_conflict: STUCK-UNION");
-                        Overlap::Arbitrary
+                        Overlap::Arbitrary { union_def: def, fields: (*f1, *f2) }
                     }
-                    _ => {
+                    None => {
                         // Different fields of a struct (`a.x` vs. `a.y`). Disjoint!
                         debug!("place_element_conflict: DISJOINT-FIELD");
                         Overlap::Disjoint
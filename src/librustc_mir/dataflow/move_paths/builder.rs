@@ -1,6 +1,7 @@
 use rustc::ty::{self, TyCtxt};
 use rustc::mir::*;
 use rustc::mir::tcx::RvalueInitializationState;
+use rustc_data_structures::bit_set::BitSet;
 use rustc_data_structures::indexed_vec::{IndexVec};
 use smallvec::{SmallVec, smallvec};
 
@@ -194,6 +195,10 @@ impl<'a, 'gcx, 'tcx> MoveDataBuilder<'a, 'gcx, 'tcx> {
             "done dumping moves"
         });
 
+        if cfg!(debug_assertions) {
+            self.data.check_consistency();
+        }
+
         if !self.errors.is_empty() {
             Err((self.data, self.errors))
         } else {
@@ -210,17 +215,28 @@ pub(super) fn gather_moves<'gcx, 'tcx>(
 
     builder.gather_args();
 
-    for (bb, block) in body.basic_blocks().iter_enumerated() {
-        for (i, stmt) in block.statements.iter().enumerate() {
-            let source = Location { block: bb, statement_index: i };
-            builder.gather_statement(source, stmt);
-        }
+    for bb in body.basic_blocks().indices() {
+        builder.gather_block(bb);
+    }
 
-        let terminator_loc = Location {
-            block: bb,
-            statement_index: block.statements.len()
-        };
-        builder.gather_terminator(terminator_loc, block.terminator());
+    builder.finalize()
+}
+
+/// Like `gather_moves`, but only gathers the moves and inits in `blocks`.
+/// The `loc_map` and `init_loc_map` entries of all other blocks are left
+/// empty, and move paths are only created for places used in `blocks`
+/// (as well as for every local, and for the arguments' initializations).
+pub(super) fn gather_moves_in<'gcx, 'tcx>(
+    body: &Body<'tcx>,
+    tcx: TyCtxt<'gcx, 'tcx>,
+    blocks: &BitSet<BasicBlock>,
+) -> Result<MoveData<'tcx>, (MoveData<'tcx>, Vec<(Place<'tcx>, MoveError<'tcx>)>)> {
+    let mut builder = MoveDataBuilder::new(body, tcx);
+
+    builder.gather_args();
+
+    for bb in blocks.iter() {
+        builder.gather_block(bb);
     }
 
     builder.finalize()
@@ -242,6 +258,21 @@ impl<'a, 'gcx, 'tcx> MoveDataBuilder<'a, 'gcx, 'tcx> {
         }
     }
 
+    fn gather_block(&mut self, bb: BasicBlock) {
+        let body = self.body;
+        let block = &body[bb];
+        for (i, stmt) in block.statements.iter().enumerate() {
+            let source = Location { block: bb, statement_index: i };
+            self.gather_statement(source, stmt);
+        }
+
+        let terminator_loc = Location {
+            block: bb,
+            statement_index: block.statements.len()
+        };
+        self.gather_terminator(terminator_loc, block.terminator());
+    }
+
     fn gather_statement(&mut self, loc: Location, stmt: &Statement<'tcx>) {
         debug!("gather_statement({:?}, {:?})", loc, stmt);
         (Gatherer { builder: self, loc }).gather_statement(stmt);
@@ -1,6 +1,8 @@
-use rustc::ty::{Ty, TyCtxt};
+use rustc::hir;
+use rustc::ty::{self, Ty, TyCtxt};
 use rustc::mir::*;
 use rustc::util::nodemap::FxHashMap;
+use rustc_data_structures::bit_set::BitSet;
 use rustc_data_structures::indexed_vec::{Idx, IndexVec};
 use smallvec::SmallVec;
 use syntax_pos::{Span};
@@ -8,7 +10,7 @@ use syntax_pos::{Span};
 use std::fmt;
 use std::ops::{Index, IndexMut};
 
-use self::abs_domain::{AbstractElem, Lift};
+use self::abs_domain::{AbstractElem, AbstractType, Lift};
 
 mod abs_domain;
 
@@ -213,6 +215,18 @@ impl Init {
     }
 }
 
+/// Coarse initialization state of a move path at some point, as
+/// computed by `MoveData::init_state_after`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum InitState {
+    /// Initialized along every path reaching the point.
+    DefinitelyInit,
+    /// Initialized along some, but not all, paths reaching the point.
+    MaybeInit,
+    /// Initialized along no path reaching the point (e.g., it was moved out of).
+    DefinitelyUninit,
+}
+
 /// Tables mapping from a place to its MovePathIndex.
 #[derive(Debug)]
 pub struct MovePathLookup {
@@ -259,9 +273,42 @@ impl MovePathLookup {
         })
     }
 
+    /// Like `find`, but returns every move path along the way, from the
+    /// root local down to the deepest tracked prefix of `place` (e.g.,
+    /// `[a, a.b, a.b.c]` for `a.b.c` if all of those are tracked).
+    ///
+    /// Returns an empty vector for places based on a static.
+    pub fn prefixes(&self, place: &Place<'tcx>) -> Vec<MovePathIndex> {
+        place.iterate(|place_base, place_projection| {
+            let mut result = match place_base {
+                PlaceBase::Local(local) => self.locals[*local],
+                PlaceBase::Static(..) => return vec![],
+            };
+            let mut prefixes = vec![result];
+
+            for proj in place_projection {
+                if let Some(&subpath) = self.projections.get(&(result, proj.elem.lift())) {
+                    result = subpath;
+                    prefixes.push(result);
+                } else {
+                    break;
+                }
+            }
+
+            prefixes
+        })
+    }
+
     pub fn find_local(&self, local: Local) -> MovePathIndex {
         self.locals[local]
     }
+
+    /// Returns the move path for the field `local.field`, or `None` if that
+    /// field is not tracked separately from `local`.
+    pub fn find_field(&self, local: Local, field: Field) -> Option<MovePathIndex> {
+        let elem = ProjectionElem::Field(field, AbstractType);
+        self.projections.get(&(self.locals[local], elem)).cloned()
+    }
 }
 
 #[derive(Debug)]
@@ -303,6 +350,112 @@ impl<'tcx> MoveError<'tcx> {
         let origin = IllegalMoveOrigin { location, kind };
         MoveError::IllegalMove { cannot_move_out_of: origin }
     }
+
+    /// Returns a value whose `Display` impl renders this error as a message
+    /// suitable for end users, e.g. "cannot move out of `*x` because it is
+    /// behind a shared reference".
+    pub fn display<'a, 'gcx>(
+        &'a self,
+        move_data: &'a MoveData<'tcx>,
+        body: &'a Body<'tcx>,
+        tcx: TyCtxt<'gcx, 'tcx>,
+    ) -> MoveErrorDisplay<'a, 'gcx, 'tcx> {
+        MoveErrorDisplay { error: self, move_data, body, tcx }
+    }
+}
+
+/// Helper returned by `MoveError::display`.
+pub struct MoveErrorDisplay<'a, 'gcx, 'tcx> {
+    error: &'a MoveError<'tcx>,
+    move_data: &'a MoveData<'tcx>,
+    body: &'a Body<'tcx>,
+    tcx: TyCtxt<'gcx, 'tcx>,
+}
+
+impl<'a, 'gcx, 'tcx> MoveErrorDisplay<'a, 'gcx, 'tcx> {
+    fn write_place(&self, w: &mut fmt::Formatter<'_>, place: &Place<'tcx>) -> fmt::Result {
+        match *place {
+            Place::Base(PlaceBase::Local(local)) => match self.body.local_decls[local].name {
+                Some(name) => write!(w, "{}", name),
+                None => write!(w, "{:?}", local),
+            },
+            Place::Base(PlaceBase::Static(box Static { kind: StaticKind::Static(def_id), .. })) => {
+                write!(w, "{}", self.tcx.def_path_str(def_id))
+            }
+            Place::Base(PlaceBase::Static(box Static { kind: StaticKind::Promoted(_), .. })) => {
+                write!(w, "promoted")
+            }
+            Place::Projection(ref proj) => match proj.elem {
+                ProjectionElem::Deref => {
+                    write!(w, "*")?;
+                    self.write_place(w, &proj.base)
+                }
+                ProjectionElem::Downcast(..) => self.write_place(w, &proj.base),
+                ProjectionElem::Field(field, _) => {
+                    self.write_place(w, &proj.base)?;
+                    let base_ty = proj.base.ty(self.body, self.tcx);
+                    let ty = if base_ty.ty.is_box() { base_ty.ty.boxed_ty() } else { base_ty.ty };
+                    match ty.sty {
+                        ty::Adt(def, _) => {
+                            let variant = match base_ty.variant_index {
+                                Some(idx) => &def.variants[idx],
+                                None => def.non_enum_variant(),
+                            };
+                            write!(w, ".{}", variant.fields[field.index()].ident)
+                        }
+                        _ => write!(w, ".{}", field.index()),
+                    }
+                }
+                ProjectionElem::Index(_)
+                | ProjectionElem::ConstantIndex { .. }
+                | ProjectionElem::Subslice { .. } => {
+                    self.write_place(w, &proj.base)?;
+                    write!(w, "[..]")
+                }
+            },
+        }
+    }
+}
+
+impl<'a, 'gcx, 'tcx> fmt::Display for MoveErrorDisplay<'a, 'gcx, 'tcx> {
+    fn fmt(&self, w: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let origin = match *self.error {
+            MoveError::IllegalMove { cannot_move_out_of: ref origin } => origin,
+            MoveError::UnionMove { path } => {
+                write!(w, "cannot move out of `")?;
+                self.write_place(w, &self.move_data.move_paths[path].place)?;
+                return write!(w, "` because it is a union");
+            }
+        };
+        match origin.kind {
+            IllegalMoveOriginKind::Static => write!(w, "cannot move out of static item"),
+            IllegalMoveOriginKind::BorrowedContent { ref target_place } => {
+                let pointer = match *target_place {
+                    Place::Projection(ref proj) => match proj.base.ty(self.body, self.tcx).ty.sty {
+                        ty::Ref(_, _, hir::MutImmutable) => "a shared reference",
+                        ty::Ref(_, _, hir::MutMutable) => "a mutable reference",
+                        ty::RawPtr(..) => "a raw pointer",
+                        _ => "a reference",
+                    },
+                    Place::Base(_) => "a reference",
+                };
+                write!(w, "cannot move out of `")?;
+                self.write_place(w, target_place)?;
+                write!(w, "` because it is behind {}", pointer)
+            }
+            IllegalMoveOriginKind::InteriorOfTypeWithDestructor { container_ty } => {
+                write!(w, "cannot move out of type `{}`, which implements the `Drop` trait",
+                       container_ty)
+            }
+            IllegalMoveOriginKind::InteriorOfSliceOrArray { ty, .. } => {
+                let kind = match ty.sty {
+                    ty::Array(..) => "array",
+                    _ => "slice",
+                };
+                write!(w, "cannot move out of type `{}`, a non-copy {}", ty, kind)
+            }
+        }
+    }
 }
 
 impl<'gcx, 'tcx> MoveData<'tcx> {
@@ -313,6 +466,90 @@ impl<'gcx, 'tcx> MoveData<'tcx> {
         builder::gather_moves(body, tcx)
     }
 
+    /// Like `gather_moves`, but only records the moves and inits in `blocks`;
+    /// the per-location data of every other block is left empty. Move paths
+    /// are indexed differently than in the move data for the whole body, so
+    /// the two must not be mixed.
+    pub fn gather_moves_in(
+        body: &Body<'tcx>,
+        tcx: TyCtxt<'gcx, 'tcx>,
+        blocks: &BitSet<BasicBlock>,
+    ) -> Result<Self, (Self, Vec<(Place<'tcx>, MoveError<'tcx>)>)> {
+        builder::gather_moves_in(body, tcx, blocks)
+    }
+
+    /// Like `gather_moves`, but always returns the move data, along with
+    /// any illegal moves encountered while gathering it. This is useful for
+    /// analyses that want best-effort move paths even when there are errors
+    /// (which are reported by borrowck).
+    pub fn gather_moves_lenient(
+        body: &Body<'tcx>,
+        tcx: TyCtxt<'gcx, 'tcx>,
+    ) -> (Self, Vec<(Place<'tcx>, MoveError<'tcx>)>) {
+        match builder::gather_moves(body, tcx) {
+            Ok(move_data) => (move_data, vec![]),
+            Err((move_data, errors)) => (move_data, errors),
+        }
+    }
+
+    /// Classifies `mpi` on exit from the statement (or terminator) at `location`.
+    ///
+    /// This does not run any dataflow itself: `maybe_inits` and `maybe_uninits`
+    /// must be the `MaybeInitializedPlaces` and `MaybeUninitializedPlaces`
+    /// states on *entry* to `location`. The moves and inits recorded at
+    /// `location` are then applied on top of them; moves and deep inits of a
+    /// parent path affect `mpi` as well.
+    pub fn init_state_after(
+        &self,
+        mpi: MovePathIndex,
+        location: Location,
+        maybe_inits: &BitSet<MovePathIndex>,
+        maybe_uninits: &BitSet<MovePathIndex>,
+    ) -> InitState {
+        let mut paths = self.move_paths[mpi].parents(&self.move_paths);
+        paths.push(mpi);
+
+        if self.loc_map[location].iter().any(|&moi| paths.contains(&self.moves[moi].path)) {
+            return InitState::DefinitelyUninit;
+        }
+
+        let initialized_here = self.init_loc_map[location].iter().any(|&ii| {
+            let init = &self.inits[ii];
+            match init.kind {
+                InitKind::Deep => paths.contains(&init.path),
+                InitKind::Shallow => init.path == mpi,
+                // Only holds on the non-panic edge, so we can't tell here.
+                InitKind::NonPanicPathOnly => false,
+            }
+        });
+        if initialized_here {
+            return InitState::DefinitelyInit;
+        }
+
+        match (maybe_inits.contains(mpi), maybe_uninits.contains(mpi)) {
+            (true, false) => InitState::DefinitelyInit,
+            (true, true) => InitState::MaybeInit,
+            (false, _) => InitState::DefinitelyUninit,
+        }
+    }
+
+    /// Returns the deepest move path that is a prefix of both `x` and `y`
+    /// (e.g., for `a.b.c` and `a.b.d` returns the path for `a.b`), or `None`
+    /// if they do not start from the same tracked path.
+    pub fn common_ancestor(&self, x: MovePathIndex, y: MovePathIndex) -> Option<MovePathIndex> {
+        let mut x_paths = self.move_paths[x].parents(&self.move_paths);
+        x_paths.push(x);
+
+        let mut curr = Some(y);
+        while let Some(mpi) = curr {
+            if x_paths.contains(&mpi) {
+                return Some(mpi);
+            }
+            curr = self.move_paths[mpi].parent;
+        }
+        None
+    }
+
     /// For the move path `mpi`, returns the root local variable (if any) that starts the path.
     /// (e.g., for a path like `a.b.c` returns `Some(a)`)
     pub fn base_local(&self, mut mpi: MovePathIndex) -> Option<Local> {
@@ -322,4 +559,135 @@ impl<'gcx, 'tcx> MoveData<'tcx> {
             if let Some(parent) = path.parent { mpi = parent; continue } else { return None }
         }
     }
+
+    /// Asserts that the move paths, moves, inits and lookup tables all agree
+    /// with each other, to catch bugs in the builder before they cause
+    /// confusing failures in the dataflow analyses using this data. The
+    /// builder calls this in compilers built with debug assertions.
+    pub fn check_consistency(&self) {
+        let num_paths = self.move_paths.len();
+        assert_eq!(self.path_map.len(), num_paths);
+        assert_eq!(self.init_path_map.len(), num_paths);
+
+        for (mpi, path) in self.move_paths.iter_enumerated() {
+            if let Some(parent) = path.parent {
+                assert!(parent.index() < num_paths, "{:?}: bad parent {:?}", mpi, parent);
+                let mut siblings = self.move_paths[parent].first_child;
+                while let Some(sibling) = siblings {
+                    if sibling == mpi {
+                        break;
+                    }
+                    siblings = self.move_paths[sibling].next_sibling;
+                }
+                assert_eq!(siblings, Some(mpi), "{:?} is not a child of its parent", mpi);
+            }
+
+            let mut child = path.first_child;
+            while let Some(c) = child {
+                assert!(c.index() < num_paths, "{:?}: bad child {:?}", mpi, c);
+                assert_eq!(self.move_paths[c].parent, Some(mpi), "{:?}: bad child {:?}", mpi, c);
+                child = self.move_paths[c].next_sibling;
+            }
+        }
+
+        for (local, &mpi) in self.rev_lookup.locals.iter_enumerated() {
+            assert_eq!(self.move_paths[mpi].place, Place::Base(PlaceBase::Local(local)));
+            assert_eq!(self.base_local(mpi), Some(local));
+        }
+        for (&(base, _), &mpi) in &self.rev_lookup.projections {
+            assert_eq!(self.move_paths[mpi].parent, Some(base), "{:?}: bad projection", mpi);
+        }
+
+        for (moi, move_out) in self.moves.iter_enumerated() {
+            assert!(move_out.path.index() < num_paths, "{:?}: bad path", moi);
+            assert!(self.loc_map[move_out.source].contains(&moi), "{:?}: not in loc_map", moi);
+            assert!(self.path_map[move_out.path].contains(&moi), "{:?}: not in path_map", moi);
+        }
+
+        for (ii, init) in self.inits.iter_enumerated() {
+            assert!(init.path.index() < num_paths, "{:?}: bad path", ii);
+            if let InitLocation::Statement(location) = init.location {
+                assert!(self.init_loc_map[location].contains(&ii), "{:?}: not in loc map", ii);
+            }
+            assert!(self.init_path_map[init.path].contains(&ii), "{:?}: not in path map", ii);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Move data for a body with the return place `_0` and an argument `_1`,
+    /// where the first statement of `bb0` moves out of `*_1`.
+    fn move_data() -> MoveData<'static> {
+        let local = |local| Place::Base(PlaceBase::Local(Local::new(local)));
+        let path = |place, parent, first_child| {
+            MovePath { next_sibling: None, first_child, parent, place }
+        };
+        let deref = Place::Projection(Box::new(Projection {
+            base: local(1),
+            elem: ProjectionElem::Deref,
+        }));
+        let (mp0, mp1, mp2) = (MovePathIndex::new(0), MovePathIndex::new(1), MovePathIndex::new(2));
+        let mo0 = MoveOutIndex::new(0);
+        let in0 = InitIndex::new(0);
+
+        let mut projections = FxHashMap::default();
+        projections.insert((mp1, ProjectionElem::Deref), mp2);
+
+        MoveData {
+            move_paths: IndexVec::from_raw(vec![
+                path(local(0), None, None),
+                path(local(1), None, Some(mp2)),
+                path(deref, Some(mp1), None),
+            ]),
+            moves: IndexVec::from_raw(vec![MoveOut {
+                path: mp2,
+                source: Location { block: BasicBlock::new(0), statement_index: 0 },
+            }]),
+            loc_map: LocationMap {
+                map: IndexVec::from_raw(vec![vec![vec![mo0].into(), SmallVec::new()]]),
+            },
+            path_map: IndexVec::from_raw(vec![SmallVec::new(), SmallVec::new(), vec![mo0].into()]),
+            rev_lookup: MovePathLookup {
+                locals: IndexVec::from_raw(vec![mp0, mp1]),
+                projections,
+            },
+            inits: IndexVec::from_raw(vec![Init {
+                path: mp1,
+                location: InitLocation::Argument(Local::new(1)),
+                kind: InitKind::Deep,
+            }]),
+            init_loc_map: LocationMap {
+                map: IndexVec::from_raw(vec![vec![SmallVec::new(), SmallVec::new()]]),
+            },
+            init_path_map: IndexVec::from_raw(vec![
+                SmallVec::new(),
+                vec![in0].into(),
+                SmallVec::new(),
+            ]),
+        }
+    }
+
+    #[test]
+    fn consistent() {
+        move_data().check_consistency();
+    }
+
+    #[test]
+    #[should_panic(expected = "mo0: not in loc_map")]
+    fn move_missing_from_loc_map() {
+        let mut move_data = move_data();
+        move_data.loc_map.map[BasicBlock::new(0)][0].clear();
+        move_data.check_consistency();
+    }
+
+    #[test]
+    #[should_panic(expected = "mp2 is not a child of its parent")]
+    fn path_missing_from_parent() {
+        let mut move_data = move_data();
+        move_data.move_paths[MovePathIndex::new(1)].first_child = None;
+        move_data.check_consistency();
+    }
 }
@@ -1,6 +1,8 @@
 use rustc::hir::def_id::DefId;
 use rustc::mir::*;
-use rustc::ty::TyCtxt;
+use rustc::ty::{self, TyCtxt};
+use syntax::ast::Name;
+use syntax::symbol::Symbol;
 
 use crate::transform::{MirPass, MirSource};
 use crate::util::patch::MirPatch;
@@ -63,16 +65,17 @@ fn add_moves_for_packed_drops_patch<'tcx>(
     let mut patch = MirPatch::new(body);
     let param_env = tcx.param_env(def_id);
 
+    let mut packed_drops = vec![];
     for (bb, data) in body.basic_blocks().iter_enumerated() {
-        let loc = Location { block: bb, statement_index: data.statements.len() };
         let terminator = data.terminator();
 
         match terminator.kind {
-            TerminatorKind::Drop { ref location, .. }
-                if util::is_disaligned(tcx, body, param_env, location) =>
-            {
-                add_move_for_packed_drop(tcx, body, &mut patch, terminator,
-                                         loc, data.is_cleanup);
+            TerminatorKind::Drop { ref location, .. } => {
+                if let Some(container) =
+                    util::packed_container_of(tcx, body, param_env, location)
+                {
+                    packed_drops.push((bb, container));
+                }
             }
             TerminatorKind::DropAndReplace { .. } => {
                 span_bug!(terminator.source_info.span,
@@ -82,6 +85,17 @@ fn add_moves_for_packed_drops_patch<'tcx>(
         }
     }
 
+    // Each packed drop adds one block, one temp, and two statements.
+    let n = packed_drops.len();
+    patch.reserve(n, n, 2 * n);
+
+    for (bb, container) in packed_drops {
+        let data = &body[bb];
+        let loc = Location { block: bb, statement_index: data.statements.len() };
+        add_move_for_packed_drop(tcx, body, &mut patch, data.terminator(),
+                                 loc, data.is_cleanup, container);
+    }
+
     patch
 }
 
@@ -92,8 +106,9 @@ fn add_move_for_packed_drop<'tcx>(
     terminator: &Terminator<'tcx>,
     loc: Location,
     is_cleanup: bool,
+    container: DefId,
 ) {
-    debug!("add_move_for_packed_drop({:?} @ {:?})", terminator, loc);
+    debug!("add_move_for_packed_drop({:?} @ {:?}, packed in {:?})", terminator, loc, container);
     let (location, target, unwind) = match terminator.kind {
         TerminatorKind::Drop { ref location, target, unwind } =>
             (location, target, unwind),
@@ -102,7 +117,12 @@ fn add_move_for_packed_drop<'tcx>(
 
     let source_info = terminator.source_info;
     let ty = location.ty(body, tcx).ty;
-    let temp = patch.new_temp(ty, terminator.source_info.span);
+    // Name the temp after the place it is moved out of, so that debuggers
+    // stopped in the drop can still tell which value is being dropped.
+    let temp = match place_debug_name(tcx, body, location) {
+        Some(name) => patch.new_named_temp(ty, name, source_info),
+        None => patch.new_temp(ty, source_info.span),
+    };
 
     let storage_dead_block = patch.new_block(BasicBlockData {
         statements: vec![Statement {
@@ -124,3 +144,35 @@ fn add_move_for_packed_drop<'tcx>(
         unwind
     });
 }
+
+/// Returns a name for `place` built from the name of its local and the names
+/// of the fields it projects to, e.g., `foo.data` for `(*_1).1` if `_1` is
+/// `foo`. Returns `None` for places based on unnamed locals or statics, and
+/// for places that index into arrays or enum variants.
+fn place_debug_name<'tcx>(
+    tcx: TyCtxt<'tcx, 'tcx>,
+    body: &Body<'tcx>,
+    place: &Place<'tcx>,
+) -> Option<Name> {
+    match place {
+        Place::Base(PlaceBase::Local(local)) => body.local_decls[*local].name,
+        Place::Base(PlaceBase::Static(_)) => None,
+        Place::Projection(box Projection { base, elem }) => {
+            let base_name = place_debug_name(tcx, body, base)?;
+            match elem {
+                ProjectionElem::Deref => Some(base_name),
+                ProjectionElem::Field(field, _) => {
+                    let field_name = match base.ty(body, tcx).ty.sty {
+                        ty::Adt(def, _) if def.is_struct() => {
+                            def.non_enum_variant().fields[field.index()].ident.to_string()
+                        }
+                        ty::Tuple(_) => field.index().to_string(),
+                        _ => return None,
+                    };
+                    Some(Symbol::intern(&format!("{}.{}", base_name, field_name)))
+                }
+                _ => None,
+            }
+        }
+    }
+}
@@ -4,10 +4,15 @@ use syntax::symbol::sym;
 use syntax_pos::Span;
 
 use rustc::ty::{self, TyCtxt};
+use rustc::util::common::time;
 use rustc::hir::def_id::DefId;
-use rustc::mir::{self, Body, Location};
+use rustc::mir::{self, Body, BorrowKind, Location};
 use rustc_data_structures::bit_set::BitSet;
 use crate::transform::{MirPass, MirSource};
+use crate::util;
+
+use crate::borrow_check::{AccessDepth, ArtificialField};
+use crate::borrow_check::places_conflict::{self, PlaceConflictBias};
 
 use crate::dataflow::{do_dataflow, DebugFormatted};
 use crate::dataflow::MoveDataParamEnv;
@@ -17,7 +22,7 @@ use crate::dataflow::{
     DefinitelyInitializedPlaces, MaybeInitializedPlaces, MaybeUninitializedPlaces
 };
 use crate::dataflow::move_paths::{MovePathIndex, LookupResult};
-use crate::dataflow::move_paths::{HasMoveData, MoveData};
+use crate::dataflow::move_paths::{HasMoveData, MoveData, MoveError};
 use crate::dataflow;
 
 use crate::dataflow::has_rustc_mir_with;
@@ -36,7 +41,7 @@ impl MirPass for SanityCheck {
 
         let attributes = tcx.get_attrs(def_id);
         let param_env = tcx.param_env(def_id);
-        let move_data = MoveData::gather_moves(body, tcx).unwrap();
+        let (move_data, move_errors) = MoveData::gather_moves_lenient(body, tcx);
         let mdpe = MoveDataParamEnv { move_data: move_data, param_env: param_env };
         let dead_unwinds = BitSet::new_empty(body.basic_blocks().len());
         let flow_inits =
@@ -52,6 +57,28 @@ impl MirPass for SanityCheck {
                         DefinitelyInitializedPlaces::new(tcx, body, &mdpe),
                         |bd, i| DebugFormatted::new(&bd.move_data().move_paths[i]));
 
+        if let Some(item) = has_rustc_mir_with(&attributes, sym::rustc_peek_helper) {
+            let cx = HelperCx {
+                tcx,
+                body,
+                param_env,
+                move_data: &mdpe.move_data,
+                move_errors: &move_errors,
+                flow_inits: &flow_inits,
+                flow_uninits: &flow_uninits,
+            };
+            let helper = item.value_str().and_then(|name| {
+                PEEK_HELPERS.iter().find(|&&(helper, _)| name.as_str() == helper)
+            });
+            match helper {
+                Some(&(_, check)) => check(&cx),
+                None => {
+                    let msg = "rustc_peek_helper attribute requires the name of a helper check";
+                    tcx.sess.span_err(item.span, msg);
+                }
+            }
+        }
+
         if has_rustc_mir_with(&attributes, sym::rustc_peek_maybe_init).is_some() {
             sanity_check_via_rustc_peek(tcx, body, def_id, &attributes, &flow_inits);
         }
@@ -217,6 +244,483 @@ fn each_block<'tcx, O>(
                                       form `&expr`"));
 }
 
+/// Reports each of the illegal `move_errors` found while gathering `move_data`,
+/// rendered with `MoveError::display`.
+fn display_move_errors<'tcx>(cx: &HelperCx<'_, 'tcx>) {
+    let HelperCx { tcx, body, move_data, move_errors, .. } = *cx;
+    for (_, error) in move_errors {
+        let span = match *error {
+            MoveError::IllegalMove { ref cannot_move_out_of } => {
+                body.source_info(cannot_move_out_of.location).span
+            }
+            MoveError::UnionMove { .. } => body.span,
+        };
+        tcx.sess.span_err(span, &error.display(move_data, body, tcx).to_string());
+    }
+}
+
+/// What the helper checks selected by `#[rustc_mir(rustc_peek_helper = "...")]`
+/// get to look at.
+struct HelperCx<'a, 'tcx> {
+    tcx: TyCtxt<'tcx, 'tcx>,
+    body: &'a Body<'tcx>,
+    param_env: ty::ParamEnv<'tcx>,
+    move_data: &'a MoveData<'tcx>,
+    move_errors: &'a [(mir::Place<'tcx>, MoveError<'tcx>)],
+    flow_inits: &'a DataflowResults<'tcx, MaybeInitializedPlaces<'a, 'tcx, 'tcx>>,
+    flow_uninits: &'a DataflowResults<'tcx, MaybeUninitializedPlaces<'a, 'tcx, 'tcx>>,
+}
+
+/// The helper checks, by the name `#[rustc_mir(rustc_peek_helper = "name")]`
+/// selects them with. Each reports what some helper of move path analysis or
+/// of the borrow checker computes for the body, mostly for the arguments of its
+/// `rustc_peek` calls, as errors; UI tests then check the results with
+/// `//~ ERROR` annotations.
+const PEEK_HELPERS: &[(&str, fn(&HelperCx<'_, '_>))] = &[
+    ("move_errors", display_move_errors),
+    ("init_state", check_init_states),
+    ("prefixes", check_prefixes),
+    ("find_field", check_find_fields),
+    ("gather_moves_in", check_gather_moves_in),
+    ("common_ancestor", check_common_ancestors),
+    ("explain_conflict", check_explain_conflicts),
+    ("static_conflict", check_static_conflicts),
+    ("strip_downcast", check_strip_downcasts),
+    ("disjoint", check_disjoint),
+    ("reborrow", check_reborrows),
+    ("any_conflict", check_any_conflict),
+    ("packed_container", check_packed_containers),
+];
+
+/// A call `rustc_peek(&place)`, along with the location of the statement
+/// that borrows `place` for it.
+struct PeekCall<'a, 'tcx> {
+    span: Span,
+    borrow: Location,
+    place: &'a mir::Place<'tcx>,
+}
+
+/// Collects the calls to `rustc_peek` in `body`, emitting an error for each
+/// call that does not have the expression form `rustc_peek(&expr)`.
+fn peek_calls<'a, 'tcx>(
+    tcx: TyCtxt<'tcx, 'tcx>,
+    body: &'a Body<'tcx>,
+) -> Vec<PeekCall<'a, 'tcx>> {
+    let mut calls = vec![];
+    for (bb, data) in body.basic_blocks().iter_enumerated() {
+        let (args, span) = match is_rustc_peek(tcx, &data.terminator) {
+            Some(args_and_span) => args_and_span,
+            None => continue,
+        };
+        assert!(args.len() == 1);
+        let borrow = match args[0] {
+            mir::Operand::Copy(ref arg) | mir::Operand::Move(ref arg) => {
+                data.statements.iter().enumerate().rev().find_map(|(i, stmt)| match stmt.kind {
+                    mir::StatementKind::Assign(
+                        ref place,
+                        box mir::Rvalue::Ref(_, mir::BorrowKind::Shared, ref peeked),
+                    ) if place == arg => Some((i, peeked)),
+                    _ => None,
+                })
+            }
+            mir::Operand::Constant(_) => None,
+        };
+        match borrow {
+            Some((statement_index, place)) => calls.push(PeekCall {
+                span,
+                borrow: Location { block: bb, statement_index },
+                place,
+            }),
+            None => {
+                let msg = "rustc_peek: argument expression \
+                           must be immediate borrow of form `&expr`";
+                tcx.sess.span_err(span, msg);
+            }
+        }
+    }
+    calls
+}
+
+/// Like `peek_calls`, but pairs up consecutive calls, emitting an error for a
+/// trailing call without a partner.
+fn peek_pairs<'a, 'tcx>(
+    tcx: TyCtxt<'tcx, 'tcx>,
+    body: &'a Body<'tcx>,
+) -> Vec<(PeekCall<'a, 'tcx>, PeekCall<'a, 'tcx>)> {
+    let mut pairs = vec![];
+    let mut calls = peek_calls(tcx, body).into_iter();
+    while let Some(first) = calls.next() {
+        match calls.next() {
+            Some(second) => pairs.push((first, second)),
+            None => tcx.sess.span_err(first.span, "rustc_peek: call has no partner"),
+        }
+    }
+    pairs
+}
+
+/// Reports, for each call `rustc_peek(&place)`, the `InitState` of `place`
+/// computed by `MoveData::init_state_after` for the last statement before the
+/// call that is not a storage marker or a fake read. (If there is no such
+/// statement in the same block, the borrow of `place` is used instead.)
+fn check_init_states<'tcx>(cx: &HelperCx<'_, 'tcx>) {
+    let HelperCx { tcx, body, flow_inits, flow_uninits, .. } = *cx;
+    let move_data = flow_inits.operator().move_data();
+    for peek in peek_calls(tcx, body) {
+        let mpi = match move_data.rev_lookup.find(peek.place) {
+            LookupResult::Exact(mpi) => mpi,
+            LookupResult::Parent(..) => {
+                tcx.sess.span_err(peek.span, "rustc_peek: argument untracked");
+                continue;
+            }
+        };
+
+        let statements = &body[peek.borrow.block].statements[..peek.borrow.statement_index];
+        let statement_index = statements.iter().rposition(|stmt| match stmt.kind {
+            mir::StatementKind::StorageLive(_) |
+            mir::StatementKind::StorageDead(_) |
+            mir::StatementKind::FakeRead(..) |
+            mir::StatementKind::Nop => false,
+            _ => true,
+        }).unwrap_or(peek.borrow.statement_index);
+        let location = Location { block: peek.borrow.block, statement_index };
+
+        let maybe_inits =
+            dataflow::state_for_location(location, flow_inits.operator(), flow_inits, body);
+        let maybe_uninits =
+            dataflow::state_for_location(location, flow_uninits.operator(), flow_uninits, body);
+        let state = move_data.init_state_after(mpi, location, &maybe_inits, &maybe_uninits);
+        tcx.sess.span_err(peek.span, &format!("rustc_peek: {:?}", state));
+    }
+}
+
+/// Reports, for each call `rustc_peek(&place)`, the places of the move paths
+/// returned by `MovePathLookup::prefixes` for `place`.
+fn check_prefixes<'tcx>(cx: &HelperCx<'_, 'tcx>) {
+    let HelperCx { tcx, body, move_data, .. } = *cx;
+    for peek in peek_calls(tcx, body) {
+        let prefixes: Vec<_> = move_data.rev_lookup.prefixes(peek.place)
+            .into_iter()
+            .map(|mpi| &move_data.move_paths[mpi].place)
+            .collect();
+        tcx.sess.span_err(peek.span, &format!("rustc_peek: prefixes {:?}", prefixes));
+    }
+}
+
+/// Reports, for each call `rustc_peek(&local.field)`, the place of the move
+/// path returned by `MovePathLookup::find_field` for `local` and `field`.
+fn check_find_fields<'tcx>(cx: &HelperCx<'_, 'tcx>) {
+    let HelperCx { tcx, body, move_data, .. } = *cx;
+    for peek in peek_calls(tcx, body) {
+        let (local, field) = match peek.place {
+            mir::Place::Projection(box mir::Projection {
+                base: mir::Place::Base(mir::PlaceBase::Local(local)),
+                elem: mir::ProjectionElem::Field(field, _),
+            }) => (*local, *field),
+            _ => {
+                tcx.sess.span_err(peek.span, "rustc_peek: argument must be a field of a local");
+                continue;
+            }
+        };
+        match move_data.rev_lookup.find_field(local, field) {
+            Some(mpi) => {
+                let msg = format!("rustc_peek: move path {:?}", move_data.move_paths[mpi].place);
+                tcx.sess.span_err(peek.span, &msg);
+            }
+            None => tcx.sess.span_err(peek.span, "rustc_peek: field untracked"),
+        }
+    }
+}
+
+/// Reports, for each call `rustc_peek(&place)`, whether `place` is moved out of
+/// in the block of the call according to the move data gathered by
+/// `MoveData::gather_moves_in` for that block alone. Also checks that this
+/// move data has the same moves and inits as `move_data`, which is gathered for
+/// the whole body, at each location of the block, and none anywhere else.
+fn check_gather_moves_in<'tcx>(cx: &HelperCx<'_, 'tcx>) {
+    let HelperCx { tcx, body, move_data, .. } = *cx;
+    // Move paths are indexed differently in the two, so compare their places.
+    fn moves_and_inits_at<'a, 'tcx>(
+        move_data: &'a MoveData<'tcx>,
+        location: Location,
+    ) -> (Vec<&'a mir::Place<'tcx>>, Vec<&'a mir::Place<'tcx>>) {
+        let moves = move_data.loc_map[location]
+            .iter()
+            .map(|&mo| &move_data.move_paths[move_data.moves[mo].path].place)
+            .collect();
+        let inits = move_data.init_loc_map[location]
+            .iter()
+            .map(|&ii| &move_data.move_paths[move_data.inits[ii].path].place)
+            .collect();
+        (moves, inits)
+    }
+
+    for peek in peek_calls(tcx, body) {
+        let block = peek.borrow.block;
+        let mut blocks = BitSet::new_empty(body.basic_blocks().len());
+        blocks.insert(block);
+        let restricted = match MoveData::gather_moves_in(body, tcx, &blocks) {
+            Ok(move_data) | Err((move_data, _)) => move_data,
+        };
+
+        let is_moved = (0..=body[block].statements.len()).any(|statement_index| {
+            let (moves, _) = moves_and_inits_at(&restricted, Location { block, statement_index });
+            moves.contains(&peek.place)
+        });
+        if is_moved {
+            tcx.sess.span_err(peek.span, "rustc_peek: moved in block");
+        } else {
+            tcx.sess.span_err(peek.span, "rustc_peek: not moved in block");
+        }
+
+        for (bb, data) in body.basic_blocks().iter_enumerated() {
+            for statement_index in 0..=data.statements.len() {
+                let location = Location { block: bb, statement_index };
+                let expected = if bb == block {
+                    moves_and_inits_at(move_data, location)
+                } else {
+                    (vec![], vec![])
+                };
+                if moves_and_inits_at(&restricted, location) != expected {
+                    let msg = format!("rustc_peek: restricted move data differs at {:?}", location);
+                    tcx.sess.span_err(peek.span, &msg);
+                }
+            }
+        }
+    }
+}
+
+/// Pairs up consecutive calls to `rustc_peek`, and reports at the second call
+/// of each pair the place of the move path returned by
+/// `MoveData::common_ancestor` for the two arguments.
+fn check_common_ancestors<'tcx>(cx: &HelperCx<'_, 'tcx>) {
+    let HelperCx { tcx, body, move_data, .. } = *cx;
+    let tracked = |peek: &PeekCall<'_, 'tcx>| match move_data.rev_lookup.find(peek.place) {
+        LookupResult::Exact(mpi) => Some(mpi),
+        LookupResult::Parent(..) => {
+            tcx.sess.span_err(peek.span, "rustc_peek: argument untracked");
+            None
+        }
+    };
+    for (first, second) in peek_pairs(tcx, body) {
+        if let (Some(x), Some(y)) = (tracked(&first), tracked(&second)) {
+            match move_data.common_ancestor(x, y) {
+                Some(mpi) => {
+                    let msg = format!("rustc_peek: common ancestor {:?}",
+                                      move_data.move_paths[mpi].place);
+                    tcx.sess.span_err(second.span, &msg);
+                }
+                None => tcx.sess.span_err(second.span, "rustc_peek: no common ancestor"),
+            }
+        }
+    }
+}
+
+/// Pairs up consecutive calls to `rustc_peek`, and reports at the second call
+/// of each pair how `places_conflict::explain_borrow_conflict_with_place`
+/// decides whether a borrow of the first argument conflicts with an access to
+/// the second: for a mutable borrow and a deep access, for a mutable borrow
+/// and a shallow access, and for a shallow borrow and a deep access.
+///
+/// Also checks that, as `AccessDepth::subsumes` claims, the borrow conflicts
+/// with an access of some depth whenever it conflicts with an access of a
+/// depth that is subsumed by it.
+fn check_explain_conflicts<'tcx>(cx: &HelperCx<'_, 'tcx>) {
+    let HelperCx { tcx, body, .. } = *cx;
+    let depths = [
+        AccessDepth::Shallow(Some(ArtificialField::ArrayLength)),
+        AccessDepth::Shallow(Some(ArtificialField::ShallowBorrow)),
+        AccessDepth::Shallow(None),
+        AccessDepth::Drop,
+        AccessDepth::Deep,
+    ];
+
+    for (borrow, access) in peek_pairs(tcx, body) {
+        let explain = |borrow_kind, access_depth| {
+            places_conflict::explain_borrow_conflict_with_place(
+                tcx,
+                body,
+                borrow.place,
+                borrow_kind,
+                access.place,
+                access_depth,
+                PlaceConflictBias::Overlap,
+                None,
+            )
+        };
+        let mut_borrow = BorrowKind::Mut { allow_two_phase_borrow: false };
+        let msg = format!(
+            "rustc_peek: {:?}, shallow access {:?}, shallow borrow {:?}",
+            explain(mut_borrow, AccessDepth::Deep),
+            explain(mut_borrow, AccessDepth::Shallow(None)),
+            explain(BorrowKind::Shallow, AccessDepth::Deep),
+        );
+        tcx.sess.span_err(access.span, &msg);
+
+        for &depth in &depths {
+            for &other in &depths {
+                if depth.subsumes(other)
+                    && explain(mut_borrow, other).is_conflict()
+                    && !explain(mut_borrow, depth).is_conflict()
+                {
+                    let msg = format!("rustc_peek: {:?} does not subsume {:?}", depth, other);
+                    tcx.sess.span_err(access.span, &msg);
+                }
+            }
+        }
+    }
+}
+
+/// Pairs up consecutive calls to `rustc_peek`, and reports at the second call
+/// of each pair the outcome of `places_conflict::equal_static_places_conflict`
+/// for a borrow of the first argument and an access to the second, along with
+/// the outcome of the general walk it short-circuits. The two must agree when
+/// the former gives a result.
+fn check_static_conflicts<'tcx>(cx: &HelperCx<'_, 'tcx>) {
+    let HelperCx { tcx, body, .. } = *cx;
+    for (borrow, access) in peek_pairs(tcx, body) {
+        let fast_path = places_conflict::equal_static_places_conflict(
+            tcx,
+            borrow.place,
+            access.place,
+        );
+        let walk = places_conflict::explain_borrow_conflict_by_walk(
+            tcx,
+            body,
+            borrow.place,
+            BorrowKind::Mut { allow_two_phase_borrow: false },
+            access.place,
+            AccessDepth::Deep,
+            PlaceConflictBias::Overlap,
+            None,
+        );
+        let msg = format!("rustc_peek: fast path {:?}, walk {:?}", fast_path, walk);
+        tcx.sess.span_err(access.span, &msg);
+    }
+}
+
+/// Reports, for each borrow of a place that goes through a downcast to an enum
+/// variant, the place returned by `places_conflict::strip_downcast` for it.
+/// Such places cannot be written as the argument of `rustc_peek`, so this looks
+/// at the borrows created by `ref` bindings in patterns instead.
+fn check_strip_downcasts<'tcx>(cx: &HelperCx<'_, 'tcx>) {
+    let HelperCx { tcx, body, .. } = *cx;
+    for data in body.basic_blocks() {
+        for statement in &data.statements {
+            let place = match statement.kind {
+                mir::StatementKind::Assign(_, box mir::Rvalue::Ref(_, _, ref place)) => place,
+                _ => continue,
+            };
+            let has_downcast = place.iterate(|_, projections| {
+                projections.into_iter().any(|projection| match projection.elem {
+                    mir::ProjectionElem::Downcast(..) => true,
+                    _ => false,
+                })
+            });
+            if has_downcast {
+                let msg = format!("rustc_peek: strip_downcast({:?}) = {:?}",
+                                  place, places_conflict::strip_downcast(place));
+                tcx.sess.span_err(statement.source_info.span, &msg);
+            }
+        }
+    }
+}
+
+/// Reports, for each call `rustc_peek(&place)`, the packed ADT returned by
+/// `util::packed_container_of` for `place`.
+fn check_packed_containers<'tcx>(cx: &HelperCx<'_, 'tcx>) {
+    let HelperCx { tcx, body, param_env, .. } = *cx;
+    for peek in peek_calls(tcx, body) {
+        match util::packed_container_of(tcx, body, param_env, peek.place) {
+            Some(container) => {
+                let msg = format!("rustc_peek: packed container {}", tcx.def_path_str(container));
+                tcx.sess.span_err(peek.span, &msg);
+            }
+            None => tcx.sess.span_err(peek.span, "rustc_peek: not disaligned"),
+        }
+    }
+}
+
+/// Pairs up consecutive calls to `rustc_peek`, and reports at the second call
+/// of each pair whether `places_conflict::places_definitely_disjoint` proves
+/// that the two arguments are disjoint.
+fn check_disjoint<'tcx>(cx: &HelperCx<'_, 'tcx>) {
+    let HelperCx { tcx, body, .. } = *cx;
+    for (first, second) in peek_pairs(tcx, body) {
+        if places_conflict::places_definitely_disjoint(tcx, body, first.place, second.place) {
+            tcx.sess.span_err(second.span, "rustc_peek: disjoint");
+        } else {
+            tcx.sess.span_err(second.span, "rustc_peek: not disjoint");
+        }
+    }
+}
+
+/// Pairs up consecutive calls to `rustc_peek`, and reports at the second call
+/// of each pair whether `places_conflict::is_reborrow_of` considers an access to
+/// the second argument a reborrow of the first.
+fn check_reborrows<'tcx>(cx: &HelperCx<'_, 'tcx>) {
+    let HelperCx { tcx, body, .. } = *cx;
+    for (borrow, access) in peek_pairs(tcx, body) {
+        if places_conflict::is_reborrow_of(borrow.place, access.place) {
+            tcx.sess.span_err(access.span, "rustc_peek: reborrow");
+        } else {
+            tcx.sess.span_err(access.span, "rustc_peek: not a reborrow");
+        }
+    }
+}
+
+/// Treats all calls to `rustc_peek` but the last as mutable borrows of their
+/// arguments, and reports at the last call whether `places_conflict::any_conflict`
+/// finds that a deep access to its argument conflicts with any of them. This
+/// must agree with checking each of the borrows in turn, and both are timed
+/// under `-Z time-passes`.
+fn check_any_conflict<'tcx>(cx: &HelperCx<'_, 'tcx>) {
+    let HelperCx { tcx, body, .. } = *cx;
+    let mut borrows = peek_calls(tcx, body);
+    let access = match borrows.pop() {
+        Some(access) => access,
+        None => return,
+    };
+    let mut_borrow = BorrowKind::Mut { allow_two_phase_borrow: false };
+    let borrows_by_root = places_conflict::group_borrows_by_root(
+        borrows.iter().map(|borrow| (borrow.place, mut_borrow)),
+    );
+
+    let any_conflict = time(tcx.sess, "any_conflict", || {
+        places_conflict::any_conflict(
+            tcx,
+            body,
+            access.place,
+            AccessDepth::Deep,
+            &borrows_by_root,
+            PlaceConflictBias::Overlap,
+        )
+    });
+    let naive_conflict = time(tcx.sess, "naive conflict loop", || {
+        borrows.iter().any(|borrow| {
+            places_conflict::explain_borrow_conflict_with_place(
+                tcx,
+                body,
+                borrow.place,
+                mut_borrow,
+                access.place,
+                AccessDepth::Deep,
+                PlaceConflictBias::Overlap,
+                None,
+            ).is_conflict()
+        })
+    });
+
+    if any_conflict != naive_conflict {
+        let msg = format!("rustc_peek: any_conflict gives {}, but the naive loop gives {}",
+                          any_conflict, naive_conflict);
+        tcx.sess.span_err(access.span, &msg);
+    } else if any_conflict {
+        tcx.sess.span_err(access.span, "rustc_peek: conflict");
+    } else {
+        tcx.sess.span_err(access.span, "rustc_peek: no conflict");
+    }
+}
+
 fn is_rustc_peek<'a, 'tcx>(
     tcx: TyCtxt<'tcx, 'tcx>,
     terminator: &'a Option<mir::Terminator<'tcx>>,
@@ -1,3 +1,4 @@
+use rustc::hir::def_id::DefId;
 use rustc::ty::{self, TyCtxt};
 use rustc::mir::*;
 
@@ -13,28 +14,50 @@ pub fn is_disaligned<'tcx, L>(
 where
     L: HasLocalDecls<'tcx>,
 {
-    debug!("is_disaligned({:?})", place);
-    if !is_within_packed(tcx, local_decls, place) {
-        debug!("is_disaligned({:?}) - not within packed", place);
-        return false
-    }
+    packed_container_of(tcx, local_decls, param_env, place).is_some()
+}
+
+/// If this place is allowed to be less aligned than its containing
+/// struct (see `is_disaligned`), returns the `DefId` of the innermost
+/// packed ADT that it is a field of.
+pub fn packed_container_of<'tcx, L>(
+    tcx: TyCtxt<'tcx, 'tcx>,
+    local_decls: &L,
+    param_env: ty::ParamEnv<'tcx>,
+    place: &Place<'tcx>,
+) -> Option<DefId>
+where
+    L: HasLocalDecls<'tcx>,
+{
+    debug!("packed_container_of({:?})", place);
+    let container = match innermost_packed_container(tcx, local_decls, place) {
+        Some(container) => container,
+        None => {
+            debug!("packed_container_of({:?}) - not within packed", place);
+            return None
+        }
+    };
 
     let ty = place.ty(local_decls, tcx).ty;
     match tcx.layout_raw(param_env.and(ty)) {
         Ok(layout) if layout.align.abi.bytes() == 1 => {
             // if the alignment is 1, the type can't be further
             // disaligned.
-            debug!("is_disaligned({:?}) - align = 1", place);
-            false
+            debug!("packed_container_of({:?}) - align = 1", place);
+            None
         }
         _ => {
-            debug!("is_disaligned({:?}) - true", place);
-            true
+            debug!("packed_container_of({:?}) - {:?}", place, container);
+            Some(container)
         }
     }
 }
 
-fn is_within_packed<'tcx, L>(tcx: TyCtxt<'tcx, 'tcx>, local_decls: &L, place: &Place<'tcx>) -> bool
+fn innermost_packed_container<'tcx, L>(
+    tcx: TyCtxt<'tcx, 'tcx>,
+    local_decls: &L,
+    place: &Place<'tcx>,
+) -> Option<DefId>
 where
     L: HasLocalDecls<'tcx>,
 {
@@ -49,7 +72,7 @@ where
                 let ty = base.ty(local_decls, tcx).ty;
                 match ty.sty {
                     ty::Adt(def, _) if def.repr.packed() => {
-                        return true
+                        return Some(def.did)
                     }
                     _ => {}
                 }
@@ -59,5 +82,5 @@ where
         place = base;
     }
 
-    false
+    None
 }
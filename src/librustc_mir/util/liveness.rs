@@ -146,6 +146,10 @@ pub fn categorize(context: PlaceContext) -> Option<DefUse> {
         // This won't affect the results since we use this analysis for generators
         // and we only care about the result at suspension points. Borrows cannot
         // cross suspension points so this behavior is unproblematic.
+        //
+        // There is no separate context for taking the address of a place:
+        // `&x as *const _` borrows `x` and then casts the reference, so
+        // both the borrow and the cast operand are regular uses here.
         PlaceContext::MutatingUse(MutatingUseContext::Borrow) |
         PlaceContext::NonMutatingUse(NonMutatingUseContext::SharedBorrow) |
         PlaceContext::NonMutatingUse(NonMutatingUseContext::ShallowBorrow) |
@@ -1,6 +1,7 @@
 use rustc::ty::Ty;
 use rustc::mir::*;
 use rustc_data_structures::indexed_vec::{IndexVec, Idx};
+use syntax::ast::Name;
 use syntax_pos::Span;
 
 /// This struct represents a patch to MIR, which can add
@@ -67,6 +68,14 @@ impl<'tcx> MirPatch<'tcx> {
         result
     }
 
+    /// Reserves capacity for at least the given number of new blocks,
+    /// new locals and new statements to be added to this patch.
+    pub fn reserve(&mut self, blocks: usize, locals: usize, statements: usize) {
+        self.new_blocks.reserve(blocks);
+        self.new_locals.reserve(locals);
+        self.new_statements.reserve(statements);
+    }
+
     pub fn resume_block(&self) -> BasicBlock {
         self.resume_block
     }
@@ -93,6 +102,24 @@ impl<'tcx> MirPatch<'tcx> {
         Local::new(index as usize)
     }
 
+    /// Like `new_temp`, but the new local is named `name` and visible in
+    /// `source_info.scope`, so that debuggers can show it.
+    pub fn new_named_temp(
+        &mut self,
+        ty: Ty<'tcx>,
+        name: Name,
+        source_info: SourceInfo,
+    ) -> Local {
+        let index = self.next_local;
+        self.next_local += 1;
+        let mut decl = LocalDecl::new_temp(ty, source_info.span);
+        decl.name = Some(name);
+        decl.source_info = source_info;
+        decl.visibility_scope = source_info.scope;
+        self.new_locals.push(decl);
+        Local::new(index as usize)
+    }
+
     pub fn new_internal(&mut self, ty: Ty<'tcx>, span: Span) -> Local {
         let index = self.next_local;
         self.next_local += 1;
@@ -11,7 +11,7 @@ fn is_homogeneous_aggregate<'a, Ty, C>(cx: &C, arg: &mut ArgType<'a, Ty>)
         let size = arg.layout.size;
 
         // Ensure we have at most four uniquely addressable members.
-        if size > unit.size.checked_mul(4, cx).unwrap() {
+        if size > unit.size.repeat(4, cx).unwrap() {
             return None;
         }
 
@@ -3,6 +3,7 @@ pub use Primitive::*;
 
 use crate::spec::Target;
 
+use std::borrow::Cow;
 use std::fmt;
 use std::ops::{Add, Deref, Sub, Mul, AddAssign, Range, RangeInclusive};
 
@@ -30,6 +31,15 @@ pub struct TargetDataLayout {
     /// Alignments for vector types.
     pub vector_align: Vec<(Size, AbiAndPrefAlign)>,
 
+    /// Natural alignment of the stack, from the `S` specifier; `None` if
+    /// it was not specified.
+    pub stack_align: Option<Align>,
+
+    /// Exclusive upper bound on object size, overriding the default for
+    /// the pointer size (see `obj_size_bound`). Must not exceed `1 << 61`,
+    /// so that sizes in bits fit in a `u64`.
+    pub max_object_size: Option<u64>,
+
     pub instruction_address_space: u32,
 }
 
@@ -54,6 +64,8 @@ impl Default for TargetDataLayout {
                 (Size::from_bits(64), AbiAndPrefAlign::new(align(64))),
                 (Size::from_bits(128), AbiAndPrefAlign::new(align(128))),
             ],
+            stack_align: None,
+            max_object_size: None,
             instruction_address_space: 0,
         }
     }
@@ -110,6 +122,15 @@ impl TargetDataLayout {
                 [p] if p.starts_with("P") => {
                     dl.instruction_address_space = parse_address_space(&p[1..], "P")?
                 }
+                [s] if s.starts_with("S") => {
+                    // `S0` means that the stack alignment is unspecified.
+                    dl.stack_align = match parse_bits(&s[1..], "alignment", "S")? {
+                        0 => None,
+                        bits => Some(Align::from_bits(bits).map_err(|err| {
+                            format!("invalid alignment for `S` in \"data-layout\": {}", err)
+                        })?),
+                    };
+                }
                 ["a", ref a..] => dl.aggregate_align = align(a, "a")?,
                 ["f32", ref a..] => dl.f32_align = align(a, "f32")?,
                 ["f64", ref a..] => dl.f64_align = align(a, "f64")?,
@@ -172,10 +193,84 @@ impl TargetDataLayout {
                                dl.pointer_size.bits(), target.target_pointer_width));
         }
 
+        // Layout computation cannot bound object sizes without this.
+        if dl.obj_size_bound().is_none() {
+            return Err(format!("unsupported target specification: the maximum object size \
+                                is not known for {}-bit pointers",
+                               dl.pointer_size.bits()));
+        }
+
         Ok(dl)
     }
 
-    /// Returns exclusive upper bound on object size.
+    /// Returns `true` if values laid out for `self` are laid out the same way for
+    /// `other`. The fields compared are `endian`, `pointer_size`, and the ABI
+    /// (not preferred) alignments of pointers, the integer types `i1` to `i128`,
+    /// `f32`, `f64` and aggregates. Preferred alignments, vector alignments,
+    /// `stack_align`, `max_object_size` and `instruction_address_space` are ignored.
+    pub fn is_abi_compatible(&self, other: &TargetDataLayout) -> bool {
+        let abi_aligns = |dl: &TargetDataLayout| [
+            dl.i1_align.abi,
+            dl.i8_align.abi,
+            dl.i16_align.abi,
+            dl.i32_align.abi,
+            dl.i64_align.abi,
+            dl.i128_align.abi,
+            dl.f32_align.abi,
+            dl.f64_align.abi,
+            dl.pointer_align.abi,
+            dl.aggregate_align.abi,
+        ];
+
+        self.endian == other.endian &&
+            self.pointer_size == other.pointer_size &&
+            abi_aligns(self) == abi_aligns(other)
+    }
+
+    /// Renders this layout as a "data-layout" string, which `parse` accepts
+    /// and turns back into this layout. Every specification is emitted, even
+    /// if it is equal to its default, except for `S` and `P`, which are left
+    /// out when they are unset (i.e., `S0` and `P0`). `max_object_size` has no
+    /// "data-layout" form, so it is not included.
+    pub fn to_data_layout_string(&self) -> String {
+        let align = |a: AbiAndPrefAlign| format!("{}:{}", a.abi.bits(), a.pref.bits());
+
+        let mut specs = vec![match self.endian {
+            Endian::Little => "e".to_string(),
+            Endian::Big => "E".to_string(),
+        }];
+        if self.instruction_address_space != 0 {
+            specs.push(format!("P{}", self.instruction_address_space));
+        }
+        specs.push(format!("p:{}:{}", self.pointer_size.bits(), align(self.pointer_align)));
+        // `i128` must come last, as `parse` also uses the largest of `i64`
+        // through `i128` for the alignment of `i128`.
+        for &(bits, a) in &[
+            (1, self.i1_align),
+            (8, self.i8_align),
+            (16, self.i16_align),
+            (32, self.i32_align),
+            (64, self.i64_align),
+            (128, self.i128_align),
+        ] {
+            specs.push(format!("i{}:{}", bits, align(a)));
+        }
+        specs.push(format!("f32:{}", align(self.f32_align)));
+        specs.push(format!("f64:{}", align(self.f64_align)));
+        for &(size, a) in &self.vector_align {
+            specs.push(format!("v{}:{}", size.bits(), align(a)));
+        }
+        specs.push(format!("a:{}", align(self.aggregate_align)));
+        if let Some(stack_align) = self.stack_align {
+            specs.push(format!("S{}", stack_align.bits()));
+        }
+        specs.join("-")
+    }
+
+    /// Returns exclusive upper bound on object size, or `None` if it is not
+    /// known for this target's pointer size and `max_object_size` is not set.
+    /// (`parse` rejects such targets, so this is only `None` for data layouts
+    /// that were not parsed from a target specification.)
     ///
     /// The theoretical maximum object size is defined as the maximum positive `isize` value.
     /// This ensures that the `offset` semantics remain well-defined by allowing it to correctly
@@ -186,12 +281,18 @@ impl TargetDataLayout {
     /// to represent object size in bits. It would need to be 1 << 61 to account for this, but is
     /// currently conservatively bounded to 1 << 47 as that is enough to cover the current usable
     /// address space on 64-bit ARMv8 and x86_64.
-    pub fn obj_size_bound(&self) -> u64 {
+    pub fn obj_size_bound(&self) -> Option<u64> {
+        if let Some(max_object_size) = self.max_object_size {
+            // Layout computation relies on sizes in bits fitting in a `u64`.
+            assert!(max_object_size <= 1 << 61,
+                    "obj_size_bound: max_object_size {} exceeds 1 << 61", max_object_size);
+            return Some(max_object_size);
+        }
         match self.pointer_size.bits() {
-            16 => 1 << 15,
-            32 => 1 << 31,
-            64 => 1 << 47,
-            bits => panic!("obj_size_bound: unknown pointer bit size {}", bits)
+            16 => Some(1 << 15),
+            32 => Some(1 << 31),
+            64 => Some(1 << 47),
+            _ => None,
         }
     }
 
@@ -204,6 +305,12 @@ impl TargetDataLayout {
         }
     }
 
+    /// Returns the natural alignment of the stack, or `fallback` if the
+    /// data layout doesn't specify one.
+    pub fn stack_align_or(&self, fallback: Align) -> Align {
+        self.stack_align.unwrap_or(fallback)
+    }
+
     pub fn vector_align(&self, vec_size: Size) -> AbiAndPrefAlign {
         for &(size, align) in &self.vector_align {
             if size == vec_size {
@@ -214,6 +321,13 @@ impl TargetDataLayout {
         // That is, use the size, rounded up to a power of 2.
         AbiAndPrefAlign::new(Align::from_bytes(vec_size.bytes().next_power_of_two()).unwrap())
     }
+
+    /// Moves the data layout to the heap and leaks it, for the common case where
+    /// it is needed for the rest of the compilation. The resulting reference can
+    /// be shared freely between threads without cloning `vector_align`.
+    pub fn leak(self) -> &'static TargetDataLayout {
+        Box::leak(Box::new(self))
+    }
 }
 
 pub trait HasDataLayout {
@@ -233,6 +347,43 @@ pub enum Endian {
     Big
 }
 
+impl Endian {
+    /// Writes `val` into all of `dst` (at most 16 bytes) in this byte order.
+    ///
+    /// Panics if `val` doesn't fit in `dst.len()` bytes.
+    pub fn write_target_uint(self, dst: &mut [u8], val: u128) {
+        let len = dst.len();
+        assert!(len <= 16, "Endian::write_target_uint: {} bytes don't fit in u128", len);
+        let bytes = val.to_le_bytes();
+        assert!(bytes[len..].iter().all(|&b| b == 0),
+                "Endian::write_target_uint: {} doesn't fit in {} bytes", val, len);
+        match self {
+            Endian::Little => dst.copy_from_slice(&bytes[..len]),
+            Endian::Big => {
+                for (d, &b) in dst.iter_mut().zip(bytes[..len].iter().rev()) {
+                    *d = b;
+                }
+            }
+        }
+    }
+
+    /// Reads an unsigned integer from all of `src` (at most 16 bytes) in this byte order.
+    pub fn read_target_uint(self, src: &[u8]) -> u128 {
+        let len = src.len();
+        assert!(len <= 16, "Endian::read_target_uint: {} bytes don't fit in u128", len);
+        let mut bytes = [0u8; 16];
+        match self {
+            Endian::Little => bytes[..len].copy_from_slice(src),
+            Endian::Big => {
+                for (b, &s) in bytes[..len].iter_mut().zip(src.iter().rev()) {
+                    *b = s;
+                }
+            }
+        }
+        u128::from_le_bytes(bytes)
+    }
+}
+
 /// Size of a type in bytes.
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, RustcEncodable, RustcDecodable)]
 pub struct Size {
@@ -256,25 +407,31 @@ impl Size {
     }
 
     #[inline]
-    pub fn bytes(self) -> u64 {
+    pub const fn bytes(self) -> u64 {
         self.raw
     }
 
     #[inline]
     pub fn bits(self) -> u64 {
-        self.bytes().checked_mul(8).unwrap_or_else(|| {
+        self.checked_bits().unwrap_or_else(|| {
             panic!("Size::bits: {} bytes in bits doesn't fit in u64", self.bytes())
         })
     }
 
+    /// Like `bits`, but returns `None` instead of panicking on overflow.
     #[inline]
-    pub fn align_to(self, align: Align) -> Size {
+    pub fn checked_bits(self) -> Option<u64> {
+        self.bytes().checked_mul(8)
+    }
+
+    #[inline]
+    pub const fn align_to(self, align: Align) -> Size {
         let mask = align.bytes() - 1;
         Size::from_bytes((self.bytes() + mask) & !mask)
     }
 
     #[inline]
-    pub fn is_aligned(self, align: Align) -> bool {
+    pub const fn is_aligned(self, align: Align) -> bool {
         let mask = align.bytes() - 1;
         self.bytes() & mask == 0
     }
@@ -285,7 +442,7 @@ impl Size {
 
         let bytes = self.bytes().checked_add(offset.bytes())?;
 
-        if bytes < dl.obj_size_bound() {
+        if bytes < dl.obj_size_bound()? {
             Some(Size::from_bytes(bytes))
         } else {
             None
@@ -297,12 +454,20 @@ impl Size {
         let dl = cx.data_layout();
 
         let bytes = self.bytes().checked_mul(count)?;
-        if bytes < dl.obj_size_bound() {
+        if bytes < dl.obj_size_bound()? {
             Some(Size::from_bytes(bytes))
         } else {
             None
         }
     }
+
+    /// Computes the size of `count` consecutive elements of this size (e.g.
+    /// an array with this stride), or `None` if that overflows or exceeds
+    /// the maximum object size of the target.
+    #[inline]
+    pub fn repeat<C: HasDataLayout>(self, count: u64, cx: &C) -> Option<Size> {
+        self.checked_mul(count, cx)
+    }
 }
 
 // Panicking addition, subtraction and multiplication for convenience.
@@ -389,11 +554,27 @@ impl Align {
         Ok(Align { pow2 })
     }
 
-    pub fn bytes(self) -> u64 {
+    /// Like `from_bytes`, but never fails: `align` is rounded up to the next
+    /// power of two, and capped at the largest alignment `from_bytes` accepts
+    /// (`2^29` bytes).
+    pub fn from_bytes_clamped(align: u64) -> Align {
+        const MAX_POW2: u8 = 29;
+
+        if align <= 1 {
+            return Align { pow2: 0 };
+        }
+        if align > 1 << MAX_POW2 {
+            return Align { pow2: MAX_POW2 };
+        }
+
+        Align { pow2: (64 - (align - 1).leading_zeros()) as u8 }
+    }
+
+    pub const fn bytes(self) -> u64 {
         1 << self.pow2
     }
 
-    pub fn bits(self) -> u64 {
+    pub const fn bits(self) -> u64 {
         self.bytes() * 8
     }
 
@@ -412,6 +593,36 @@ impl Align {
     pub fn restrict_for_offset(self, offset: Size) -> Align {
         self.min(Align::max_for_offset(offset))
     }
+
+    /// Returns the stricter (i.e., larger) of the two alignments. Anything
+    /// aligned to the result is aligned to both.
+    ///
+    /// ```
+    /// use rustc_target::abi::Align;
+    ///
+    /// let align = |bytes| Align::from_bytes(bytes).unwrap();
+    /// assert_eq!(align(4).stricter(align(8)), align(8));
+    /// assert_eq!(align(8).stricter(align(4)), align(8));
+    /// ```
+    #[inline]
+    pub fn stricter(self, other: Align) -> Align {
+        self.max(other)
+    }
+
+    /// Returns the looser (i.e., smaller) of the two alignments, which anything
+    /// aligned to either alignment is aligned to.
+    ///
+    /// ```
+    /// use rustc_target::abi::Align;
+    ///
+    /// let align = |bytes| Align::from_bytes(bytes).unwrap();
+    /// assert_eq!(align(4).looser(align(8)), align(4));
+    /// assert_eq!(align(8).looser(align(8)), align(8));
+    /// ```
+    #[inline]
+    pub fn looser(self, other: Align) -> Align {
+        self.min(other)
+    }
 }
 
 /// A pair of aligments, ABI-mandated and preferred.
@@ -422,7 +633,7 @@ pub struct AbiAndPrefAlign {
 }
 
 impl AbiAndPrefAlign {
-    pub fn new(align: Align) -> AbiAndPrefAlign {
+    pub const fn new(align: Align) -> AbiAndPrefAlign {
         AbiAndPrefAlign {
             abi: align,
             pref: align,
@@ -455,6 +666,16 @@ pub enum Integer {
 }
 
 impl Integer {
+    /// Returns all integer types, from smallest to largest.
+    pub fn all() -> impl DoubleEndedIterator<Item = Integer> {
+        [I8, I16, I32, I64, I128].iter().cloned()
+    }
+
+    /// Returns all integer types, from largest to smallest.
+    pub fn all_descending() -> impl Iterator<Item = Integer> {
+        Integer::all().rev()
+    }
+
     pub fn size(self) -> Size {
         match self {
             I8 => Size::from_bytes(1),
@@ -503,7 +724,7 @@ impl Integer {
     pub fn for_align<C: HasDataLayout>(cx: &C, wanted: Align) -> Option<Integer> {
         let dl = cx.data_layout();
 
-        for &candidate in &[I8, I16, I32, I64, I128] {
+        for candidate in Integer::all() {
             if wanted == candidate.align(dl).abi && wanted.bytes() == candidate.size().bytes() {
                 return Some(candidate);
             }
@@ -516,7 +737,7 @@ impl Integer {
         let dl = cx.data_layout();
 
         // FIXME(eddyb) maybe include I128 in the future, when it works everywhere.
-        for &candidate in &[I64, I32, I16] {
+        for candidate in Integer::all_descending().filter(|&i| I16 <= i && i <= I64) {
             if wanted >= candidate.align(dl).abi && wanted.bytes() >= candidate.size().bytes() {
                 return candidate;
             }
@@ -619,6 +840,21 @@ impl Primitive {
             _ => false,
         }
     }
+
+    pub fn is_ptr(self) -> bool {
+        match self {
+            Pointer => true,
+            _ => false,
+        }
+    }
+
+    /// Returns the size of a pointer if this is a pointer, `None` otherwise.
+    pub fn pointer_width<C: HasDataLayout>(self, cx: &C) -> Option<Size> {
+        match self {
+            Pointer => Some(cx.data_layout().pointer_size),
+            _ => None,
+        }
+    }
 }
 
 /// Information about one scalar component of a Rust type.
@@ -644,6 +880,19 @@ pub struct Scalar {
 }
 
 impl Scalar {
+    /// Creates a scalar of the given primitive which accepts every bit pattern,
+    /// i.e., with a valid range of `0..=max` for its size.
+    ///
+    /// Note that this does not special-case `bool`, which only accepts `0..=1`.
+    pub fn full<C: HasDataLayout>(value: Primitive, cx: &C) -> Scalar {
+        let bits = value.size(cx).bits();
+        assert!(bits <= 128);
+        Scalar {
+            value,
+            valid_range: 0..=(!0u128 >> (128 - bits)),
+        }
+    }
+
     pub fn is_bool(&self) -> bool {
         if let Int(I8, _) = self.value {
             self.valid_range == (0..=1)
@@ -668,6 +917,64 @@ impl Scalar {
         assert_eq!(end, end & mask);
         start..(end.wrapping_add(1) & mask)
     }
+
+    /// For an integer scalar, returns the smallest `Integer` of the same
+    /// signedness whose values include all of the valid range, e.g., `I8` for
+    /// a `u32` limited to `0..=200`, or for an `i32` limited to `-2..=1`. A
+    /// range wrapping around the maximum value of the scalar's own type is
+    /// covered by that type only. Returns `None` for non-integer scalars.
+    pub fn smallest_covering_integer(&self) -> Option<Integer> {
+        let (integer, signed) = match self.value {
+            Int(integer, signed) => (integer, signed),
+            _ => return None,
+        };
+        let (start, end) = (*self.valid_range.start(), *self.valid_range.end());
+        if signed {
+            let shift = 128 - integer.size().bits();
+            let sign_extend = |x: u128| ((x << shift) as i128) >> shift;
+            let (start, end) = (sign_extend(start), sign_extend(end));
+            if start <= end {
+                return Some(Integer::fit_signed(start).max(Integer::fit_signed(end)));
+            }
+        } else if start <= end {
+            return Some(Integer::fit_unsigned(end));
+        }
+        Some(integer)
+    }
+
+    /// Returns `true` if `self` and `other` have the same primitive,
+    /// regardless of their valid ranges (e.g., `bool` and `u8`).
+    pub fn same_primitive(&self, other: &Scalar) -> bool {
+        self.value == other.value
+    }
+
+    /// Returns `true` if every value valid for `other` is also valid for
+    /// `self`, taking wrap-around ranges into account. Both scalars are
+    /// expected to have primitives of the same size.
+    pub fn range_contains<C: HasDataLayout>(&self, other: &Scalar, cx: &C) -> bool {
+        let bits = self.value.size(cx).bits();
+        assert!(bits <= 128);
+        let max = !0u128 >> (128 - bits);
+        let (start, end) = (*self.valid_range.start(), *self.valid_range.end());
+
+        // Whether `self` contains all of `lo..=hi`, where `lo <= hi`.
+        let contains = |lo: u128, hi: u128| {
+            if start <= end {
+                start <= lo && hi <= end
+            } else {
+                // Valid values are `start..=max` and `0..=end`, so an
+                // interval must fit in one of them, unless they are adjacent.
+                lo >= start || hi <= end || start == end + 1
+            }
+        };
+
+        let (other_start, other_end) = (*other.valid_range.start(), *other.valid_range.end());
+        if other_start <= other_end {
+            contains(other_start, other_end)
+        } else {
+            contains(other_start, max) && contains(0, other_end)
+        }
+    }
 }
 
 /// Describes how the fields of a type are located in memory.
@@ -730,6 +1037,17 @@ impl FieldPlacement {
         }
     }
 
+    /// Gets the offsets of all fields, in source order (i.e. the order the
+    /// fields are declared in), so that `offsets()[i] == offset(i)`.
+    /// Only `Arbitrary` stores its offsets; for the other placements
+    /// they are computed on the fly.
+    pub fn offsets(&self) -> Cow<'_, [Size]> {
+        match *self {
+            FieldPlacement::Arbitrary { ref offsets, .. } => Cow::Borrowed(offsets),
+            _ => Cow::Owned((0..self.count()).map(|i| self.offset(i)).collect()),
+        }
+    }
+
     pub fn memory_index(&self, i: usize) -> usize {
         match *self {
             FieldPlacement::Union(_) |
@@ -774,6 +1092,45 @@ impl FieldPlacement {
             }
         })
     }
+
+    /// Gets `(source_index, offset)` pairs for the fields, by increasing offsets.
+    #[inline]
+    pub fn fields_in_memory_order<'a>(&'a self) -> impl Iterator<Item=(usize, Size)>+'a {
+        self.index_by_increasing_offset().map(move |i| (i, self.offset(i)))
+    }
+
+    /// Returns `true` if some of the `total_size` bytes are not covered by any
+    /// field, given the layouts of the fields in source order (for `Array`, only
+    /// the element layout is used). Note that, as explained on `Arbitrary`, such
+    /// gaps are not always padding that may be discarded.
+    pub fn has_padding(&self, field_layouts: &[LayoutDetails], total_size: Size) -> bool {
+        match *self {
+            FieldPlacement::Union(count) => {
+                let max_size = field_layouts[..count]
+                    .iter()
+                    .map(|field| field.size)
+                    .max()
+                    .unwrap_or(Size::ZERO);
+                max_size < total_size
+            }
+            FieldPlacement::Array { stride, count } => {
+                if count == 0 {
+                    return total_size > Size::ZERO;
+                }
+                field_layouts[0].size < stride || stride * count < total_size
+            }
+            FieldPlacement::Arbitrary { .. } => {
+                let mut end = Size::ZERO;
+                for (i, offset) in self.fields_in_memory_order() {
+                    if offset > end {
+                        return true;
+                    }
+                    end = end.max(offset + field_layouts[i].size);
+                }
+                end < total_size
+            }
+        }
+    }
 }
 
 /// Describes how values of the type are passed by target ABIs,
@@ -823,6 +1180,29 @@ impl Abi {
             _ => false,
         }
     }
+
+    /// Returns `true` if this is a single scalar whose size is a power of two
+    /// no larger than a pointer, i.e. one that fits a general-purpose register.
+    pub fn is_register_scalar<C: HasDataLayout>(&self, cx: &C) -> bool {
+        match *self {
+            Abi::Scalar(ref scal) => {
+                let size = scal.value.size(cx);
+                size.bytes().is_power_of_two() && size <= cx.data_layout().pointer_size
+            }
+            _ => false,
+        }
+    }
+}
+
+/// How a layout is passed across an FFI boundary, as far as it can be told
+/// from its `Abi` alone (see `TyLayout::ffi_classification`).
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum FfiClass {
+    Uninhabited,
+    Scalar,
+    ScalarPair,
+    Vector,
+    Aggregate { sized: bool },
 }
 
 newtype_index! {
@@ -847,6 +1227,96 @@ pub enum Variants {
     },
 }
 
+impl Variants {
+    /// Decodes the discriminant of an enum value from its raw `bytes`, given the
+    /// offset of the discriminant field (field `discr_index` of the enum layout).
+    /// Returns `None` for `Single` layouts, or if `bytes` does not cover the
+    /// discriminant field.
+    ///
+    /// Both encodings produce the discriminant value, with signed values
+    /// sign-extended to 128 bits: for `Tag`, that is the tag itself; for `Niche`,
+    /// it is the index of the decoded variant, as enums with a niche layout never
+    /// have explicit discriminants.
+    ///
+    /// This takes the data layout rather than just an `Endian`, because the size
+    /// of the discriminant also depends on the target when the niche is a pointer
+    /// (as in `Option<&T>`).
+    pub fn read_discriminant_value<C: HasDataLayout>(
+        &self,
+        bytes: &[u8],
+        discr_offset: Size,
+        cx: &C,
+    ) -> Option<u128> {
+        let (discr, discr_kind) = match *self {
+            Variants::Single { .. } => return None,
+            Variants::Multiple { ref discr, ref discr_kind, .. } => (discr, discr_kind),
+        };
+        let dl = cx.data_layout();
+        let size = discr.value.size(dl);
+        let start = discr_offset.bytes() as usize;
+        let end = start.checked_add(size.bytes() as usize)?;
+        let raw = dl.endian.read_target_uint(bytes.get(start..end)?);
+
+        match *discr_kind {
+            DiscriminantKind::Tag => match discr.value {
+                Int(_, true) => {
+                    let shift = 128 - size.bits();
+                    Some((((raw << shift) as i128) >> shift) as u128)
+                }
+                _ => Some(raw),
+            },
+            DiscriminantKind::Niche { dataful_variant, ref niche_variants, niche_start } => {
+                let variants_start = niche_variants.start().as_u32() as u128;
+                let variants_end = niche_variants.end().as_u32() as u128;
+                // The niche values may wrap around the end of the niche's type,
+                // so the arithmetic has to be done modulo its size.
+                let mask = !0u128 >> (128 - size.bits());
+                let adjusted = raw.wrapping_sub(niche_start).wrapping_add(variants_start) & mask;
+                if variants_start <= adjusted && adjusted <= variants_end {
+                    Some(adjusted)
+                } else {
+                    Some(dataful_variant.as_u32() as u128)
+                }
+            }
+        }
+    }
+
+    /// Returns the value stored in the discriminant field for variant `index`,
+    /// whose discriminant is `discr_val` (e.g., from `AdtDef::discriminants`),
+    /// i.e., the inverse of `read_discriminant_value` up to sign extension. For
+    /// `Tag`, this is `discr_val` truncated to the size of the tag; a `Niche`
+    /// encodes the index of the variant, as enums with a niche layout never
+    /// have explicit discriminants. Returns `None` for `Single` layouts, and for
+    /// variants of a `Niche` layout that do not store a value in the niche
+    /// (e.g., `dataful_variant`).
+    pub fn discriminant_for_variant<C: HasDataLayout>(
+        &self,
+        cx: &C,
+        index: VariantIdx,
+        discr_val: u128,
+    ) -> Option<u128> {
+        let (discr, discr_kind) = match *self {
+            Variants::Single { .. } => return None,
+            Variants::Multiple { ref discr, ref discr_kind, .. } => (discr, discr_kind),
+        };
+        let size = discr.value.size(cx);
+        let mask = !0u128 >> (128 - size.bits());
+
+        match *discr_kind {
+            DiscriminantKind::Tag => Some(discr_val & mask),
+            DiscriminantKind::Niche { ref niche_variants, niche_start, .. } => {
+                if !niche_variants.contains(&index) {
+                    return None;
+                }
+                // Like in `read_discriminant_value`, the niche values may wrap
+                // around the end of the niche's type.
+                let relative = (index.as_u32() - niche_variants.start().as_u32()) as u128;
+                Some(relative.wrapping_add(niche_start) & mask)
+            }
+        }
+    }
+}
+
 #[derive(PartialEq, Eq, Hash, Debug)]
 pub enum DiscriminantKind {
     /// Integer tag holding the discriminant value itself.
@@ -889,6 +1359,47 @@ impl LayoutDetails {
             align,
         }
     }
+
+    /// Returns the size of this layout in bits, or `None` if that does not
+    /// fit in a `u64`, so callers can report an error rather than panicking.
+    pub fn size_in_bits_checked(&self) -> Option<u64> {
+        self.size.checked_bits()
+    }
+
+    /// Returns the size of the payload of variant `index`: the size of that
+    /// variant's layout for `Multiple`, or the whole size for `Single` if
+    /// `index` is the variant it describes. Returns `None` otherwise.
+    pub fn variant_size(&self, index: VariantIdx) -> Option<Size> {
+        self.variant_details(index).map(|variant| variant.size)
+    }
+
+    /// Returns the alignment of variant `index`, like `variant_size`.
+    pub fn variant_align(&self, index: VariantIdx) -> Option<AbiAndPrefAlign> {
+        self.variant_details(index).map(|variant| variant.align)
+    }
+
+    /// Returns the offset of the niche holding the discriminant of a
+    /// niche-encoded enum, or `None` if the discriminant is stored in a tag
+    /// or there is only one variant.
+    pub fn niche_offset(&self) -> Option<Size> {
+        match self.variants {
+            Variants::Multiple {
+                discr_kind: DiscriminantKind::Niche { .. },
+                discr_index,
+                ..
+            } => Some(self.fields.offset(discr_index)),
+            _ => None,
+        }
+    }
+
+    fn variant_details(&self, index: VariantIdx) -> Option<&LayoutDetails> {
+        match self.variants {
+            Variants::Single { index: single } => {
+                if single == index { Some(self) } else { None }
+            }
+            Variants::Multiple { ref variants, .. } => variants.get(index),
+        }
+    }
 }
 
 /// The details of the layout of a type, alongside the type itself.
@@ -916,6 +1427,13 @@ pub trait LayoutOf {
     type TyLayout;
 
     fn layout_of(&self, ty: Self::Ty) -> Self::TyLayout;
+
+    /// Computes the layouts of all of `tys`, in order. Implementors that can
+    /// compute several layouts more cheaply than one at a time may override
+    /// this; by default it just calls `layout_of` for each type.
+    fn layout_of_many(&self, tys: impl IntoIterator<Item = Self::Ty>) -> Vec<Self::TyLayout> {
+        tys.into_iter().map(|ty| self.layout_of(ty)).collect()
+    }
 }
 
 #[derive(Copy, Clone, PartialEq, Eq)]
@@ -967,14 +1485,210 @@ impl<'a, Ty> TyLayout<'a, Ty> {
     where Ty: TyLayoutMethods<'a, C>, C: LayoutOf<Ty = Ty> {
         Ty::pointee_info_at(self, cx, offset)
     }
+
+    /// Returns the indices of the fields whose layout is uninhabited, in
+    /// increasing order. This is always empty for unions, since a union is
+    /// only uninhabited if all of its fields are.
+    pub fn uninhabited_field_indices<C>(self, cx: &C) -> Vec<usize>
+    where Ty: TyLayoutMethods<'a, C> + Copy, C: LayoutOf<Ty = Ty, TyLayout = Self> {
+        if let FieldPlacement::Union(_) = self.fields {
+            return vec![];
+        }
+        (0..self.fields.count())
+            .filter(|&i| self.field(cx, i).abi.is_uninhabited())
+            .collect()
+    }
+
+    /// Returns the largest ABI alignment among the fields of this layout,
+    /// or the ABI alignment of the layout itself if it has no fields.
+    pub fn max_field_align<C>(self, cx: &C) -> Align
+    where Ty: TyLayoutMethods<'a, C> + Copy, C: LayoutOf<Ty = Ty, TyLayout = Self> {
+        (0..self.fields.count())
+            .map(|i| self.field(cx, i).align.abi)
+            .max()
+            .unwrap_or(self.align.abi)
+    }
+
+    /// Returns an iterator over the index, offset and layout of each field, in
+    /// declaration order. Unlike `self.fields`, this includes the layouts of the
+    /// fields, which are only computed as the iterator is advanced.
+    pub fn fields_with_layouts<'c, C>(self, cx: &'c C) -> FieldsWithLayouts<'a, 'c, Ty, C>
+    where Ty: TyLayoutMethods<'a, C> + Copy, C: LayoutOf<Ty = Ty> {
+        FieldsWithLayouts { layout: self, cx, indices: 0..self.fields.count() }
+    }
+
+    /// If this layout consists of nothing but (possibly nested) fields of a single
+    /// scalar type, without any padding, returns that scalar and the number of such
+    /// fields, e.g., `(f32, 4)` for both `[f32; 4]` and `struct S([f32; 2], f32, f32)`.
+    /// Scalars are considered the same type if their `Primitive` is. Zero-sized
+    /// fields are ignored; unions and vectors are not considered homogeneous.
+    ///
+    /// Unlike `homogeneous_aggregate` in `abi::call`, which classifies fields by the
+    /// kind and size of register they are passed in, this returns the scalar itself.
+    pub fn homogeneous_scalar_elements<C>(self, cx: &C) -> Option<(Scalar, u64)>
+    where Ty: TyLayoutMethods<'a, C> + Copy, C: LayoutOf<Ty = Ty, TyLayout = Self> {
+        self.homogeneous_scalar_leaves(cx).map(|(scalar, _, count)| (scalar, count))
+    }
+
+    /// Like `homogeneous_scalar_elements`, but also returns the size of the scalar.
+    fn homogeneous_scalar_leaves<C>(self, cx: &C) -> Option<(Scalar, Size, u64)>
+    where Ty: TyLayoutMethods<'a, C> + Copy, C: LayoutOf<Ty = Ty, TyLayout = Self> {
+        match self.abi {
+            Abi::Uninhabited | Abi::Vector { .. } => return None,
+            Abi::Scalar(ref scalar) => return Some((scalar.clone(), self.size, 1)),
+            Abi::ScalarPair(..) | Abi::Aggregate { .. } => {}
+        }
+
+        let (scalar, leaf_size, count) = match self.fields {
+            FieldPlacement::Union(_) => return None,
+            FieldPlacement::Array { count, .. } => {
+                if count == 0 {
+                    return None;
+                }
+                let (scalar, leaf_size, elem_count) =
+                    self.field(cx, 0).homogeneous_scalar_leaves(cx)?;
+                (scalar, leaf_size, elem_count.checked_mul(count)?)
+            }
+            FieldPlacement::Arbitrary { .. } => {
+                let mut result: Option<(Scalar, Size, u64)> = None;
+                for i in 0..self.fields.count() {
+                    let field = self.field(cx, i);
+                    if field.is_zst() {
+                        continue;
+                    }
+                    let (scalar, leaf_size, count) = field.homogeneous_scalar_leaves(cx)?;
+                    result = match result {
+                        None => Some((scalar, leaf_size, count)),
+                        Some((prev, _, _)) if prev.value != scalar.value => return None,
+                        Some((prev, prev_size, prev_count)) => {
+                            Some((prev, prev_size, prev_count + count))
+                        }
+                    };
+                }
+                result?
+            }
+        };
+
+        // Fields never overlap (except in unions, handled above), so if they take
+        // up the whole size, there is no padding before, between or after them.
+        if leaf_size.bytes().checked_mul(count)? != self.size.bytes() {
+            return None;
+        }
+        Some((scalar, leaf_size, count))
+    }
 }
 
+/// The iterator returned by `TyLayout::fields_with_layouts`. This is a named
+/// type, rather than `impl Iterator`, because it borrows both the layout and
+/// the layout context, with unrelated lifetimes.
+pub struct FieldsWithLayouts<'a, 'c, Ty, C> {
+    layout: TyLayout<'a, Ty>,
+    cx: &'c C,
+    indices: Range<usize>,
+}
+
+impl<'a, 'c, Ty, C> Iterator for FieldsWithLayouts<'a, 'c, Ty, C>
+where Ty: TyLayoutMethods<'a, C> + Copy, C: LayoutOf<Ty = Ty> {
+    type Item = (usize, Size, C::TyLayout);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let i = self.indices.next()?;
+        Some((i, self.layout.fields.offset(i), self.layout.field(self.cx, i)))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.indices.size_hint()
+    }
+}
+
+impl<'a, 'c, Ty, C> ExactSizeIterator for FieldsWithLayouts<'a, 'c, Ty, C>
+where Ty: TyLayoutMethods<'a, C> + Copy, C: LayoutOf<Ty = Ty> {}
+
 impl<'a, Ty> TyLayout<'a, Ty> {
     /// Returns `true` if the layout corresponds to an unsized type.
     pub fn is_unsized(&self) -> bool {
         self.abi.is_unsized()
     }
 
+    /// Returns `true` if `size` is the exact size of every value of this
+    /// type, i.e., unless the type is unsized.
+    pub fn is_exact_size(&self) -> bool {
+        match self.abi {
+            Abi::Aggregate { sized: false } => false,
+            _ => true,
+        }
+    }
+
+    /// Returns the minimum size of a value of this type. For unsized types,
+    /// this is the size of the sized prefix; values may be larger depending
+    /// on their metadata. For all other types, this is the exact size.
+    pub fn min_size(&self) -> Size {
+        self.size
+    }
+
+    /// Classifies this layout for FFI purposes based on its `Abi`.
+    pub fn ffi_classification(&self) -> FfiClass {
+        match self.abi {
+            Abi::Uninhabited => FfiClass::Uninhabited,
+            Abi::Scalar(_) => FfiClass::Scalar,
+            Abi::ScalarPair(..) => FfiClass::ScalarPair,
+            Abi::Vector { .. } => FfiClass::Vector,
+            Abi::Aggregate { sized } => FfiClass::Aggregate { sized },
+        }
+    }
+
+    /// Returns `true` if this layout is passed as an `Abi::ScalarPair`. This
+    /// says nothing about whether that is meaningful to C: `&[u8]` and
+    /// `#[repr(C)] struct S { a: u32, b: u32 }` are both scalar pairs, so
+    /// callers need to look at `ffi_scalar_pair_components` to tell them apart.
+    pub fn is_ffi_scalar_pair(&self) -> bool {
+        self.ffi_classification() == FfiClass::ScalarPair
+    }
+
+    /// For an `Abi::ScalarPair` layout, returns its two scalars.
+    pub fn ffi_scalar_pair_components(&self) -> Option<(&'a Scalar, &'a Scalar)> {
+        match self.details.abi {
+            Abi::ScalarPair(ref a, ref b) => Some((a, b)),
+            _ => None,
+        }
+    }
+
+    /// For an `Abi::ScalarPair` layout, returns the layouts of its two
+    /// components, as standalone scalars, and the offset of the second one,
+    /// which starts at the size of the first, rounded up to its own ABI
+    /// alignment.
+    pub fn scalar_pair_components<C: HasDataLayout>(
+        &self,
+        cx: &C,
+    ) -> Option<(LayoutDetails, LayoutDetails, Size)> {
+        match self.abi {
+            Abi::ScalarPair(ref a, ref b) => Some((
+                LayoutDetails::scalar(cx, a.clone()),
+                LayoutDetails::scalar(cx, b.clone()),
+                a.value.size(cx).align_to(b.value.align(cx).abi),
+            )),
+            _ => None,
+        }
+    }
+
+    /// Returns the indices of the variants which can actually be constructed,
+    /// i.e., whose layout is not `Abi::Uninhabited`, in increasing order.
+    pub fn inhabited_variants(&self) -> impl Iterator<Item = VariantIdx> + 'a {
+        let details = self.details;
+        let (single, multiple) = match details.variants {
+            Variants::Single { index } => {
+                (Some(index).filter(|_| !details.abi.is_uninhabited()), None)
+            }
+            Variants::Multiple { ref variants, .. } => {
+                let inhabited = variants.iter_enumerated()
+                    .filter(|(_, variant)| !variant.abi.is_uninhabited())
+                    .map(|(i, _)| i);
+                (None, Some(inhabited))
+            }
+        };
+        single.into_iter().chain(multiple.into_iter().flatten())
+    }
+
     /// Returns `true` if the type is a ZST and not unsized.
     pub fn is_zst(&self) -> bool {
         match self.abi {
@@ -986,3 +1700,1021 @@ impl<'a, Ty> TyLayout<'a, Ty> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Fixtures shared by the layout tests below.
+
+    fn align(bytes: u64) -> Align {
+        Align::from_bytes(bytes).unwrap()
+    }
+
+    fn struct_fields(offsets: &[u64], memory_index: &[u32]) -> FieldPlacement {
+        FieldPlacement::Arbitrary {
+            offsets: offsets.iter().map(|&offset| Size::from_bytes(offset)).collect(),
+            memory_index: memory_index.to_vec(),
+        }
+    }
+
+    /// A sized, single-variant aggregate with the given fields.
+    fn aggregate(fields: FieldPlacement, align: Align, size: u64) -> LayoutDetails {
+        LayoutDetails {
+            variants: Variants::Single { index: VariantIdx::new(0) },
+            fields,
+            abi: Abi::Aggregate { sized: true },
+            align: AbiAndPrefAlign::new(align),
+            size: Size::from_bytes(size),
+        }
+    }
+
+    fn uninhabited_layout() -> LayoutDetails {
+        LayoutDetails {
+            variants: Variants::Single { index: VariantIdx::new(0) },
+            fields: FieldPlacement::Union(0),
+            abi: Abi::Uninhabited,
+            align: AbiAndPrefAlign::new(align(1)),
+            size: Size::ZERO,
+        }
+    }
+
+    fn enum_variants(
+        dl: &TargetDataLayout,
+        value: Primitive,
+        discr_kind: DiscriminantKind,
+    ) -> Variants {
+        Variants::Multiple {
+            discr: Scalar::full(value, dl),
+            discr_kind,
+            discr_index: 0,
+            variants: IndexVec::from_raw(vec![]),
+        }
+    }
+
+    #[derive(Copy, Clone, Debug)]
+    enum FieldTestTy {
+        U8,
+        U32,
+        U64,
+        F32,
+        Never,
+        Aggregate(&'static [FieldTestTy]),
+    }
+
+    struct FieldTestCx {
+        u8_layout: LayoutDetails,
+        u32_layout: LayoutDetails,
+        u64_layout: LayoutDetails,
+        f32_layout: LayoutDetails,
+        never_layout: LayoutDetails,
+    }
+
+    impl FieldTestCx {
+        fn new(dl: &TargetDataLayout) -> Self {
+            FieldTestCx {
+                u8_layout: LayoutDetails::scalar(dl, Scalar::full(Int(I8, false), dl)),
+                u32_layout: LayoutDetails::scalar(dl, Scalar::full(Int(I32, false), dl)),
+                u64_layout: LayoutDetails::scalar(dl, Scalar::full(Int(I64, false), dl)),
+                f32_layout: LayoutDetails::scalar(dl, Scalar::full(Float(FloatTy::F32), dl)),
+                never_layout: uninhabited_layout(),
+            }
+        }
+    }
+
+    impl<'a> LayoutOf for &'a FieldTestCx {
+        type Ty = FieldTestTy;
+        type TyLayout = TyLayout<'a, FieldTestTy>;
+
+        fn layout_of(&self, ty: FieldTestTy) -> Self::TyLayout {
+            let details = match ty {
+                FieldTestTy::U8 => &self.u8_layout,
+                FieldTestTy::U32 => &self.u32_layout,
+                FieldTestTy::U64 => &self.u64_layout,
+                FieldTestTy::F32 => &self.f32_layout,
+                FieldTestTy::Never => &self.never_layout,
+                FieldTestTy::Aggregate(_) => unreachable!(),
+            };
+            TyLayout { ty, details }
+        }
+    }
+
+    impl<'a> TyLayoutMethods<'a, &'a FieldTestCx> for FieldTestTy {
+        fn for_variant(
+            this: TyLayout<'a, Self>,
+            _: &&'a FieldTestCx,
+            _: VariantIdx,
+        ) -> TyLayout<'a, Self> {
+            this
+        }
+
+        fn field(this: TyLayout<'a, Self>, cx: &&'a FieldTestCx, i: usize) -> TyLayout<'a, Self> {
+            match this.ty {
+                FieldTestTy::Aggregate(fields) => cx.layout_of(fields[i]),
+                _ => unreachable!(),
+            }
+        }
+
+        fn pointee_info_at(
+            _: TyLayout<'a, Self>,
+            _: &&'a FieldTestCx,
+            _: Size,
+        ) -> Option<PointeeInfo> {
+            None
+        }
+    }
+
+    #[test]
+    fn test_target_uint_round_trip() {
+        for &endian in &[Endian::Little, Endian::Big] {
+            for &(len, val) in &[
+                (0, 0),
+                (1, 0xab),
+                (2, 0xabcd),
+                (4, 0x0102_0304),
+                (8, 0x0102_0304_0506_0708),
+                (16, 0x0102_0304_0506_0708_090a_0b0c_0d0e_0f10),
+                (16, !0),
+            ] {
+                let mut buf = vec![0u8; len];
+                endian.write_target_uint(&mut buf, val);
+                assert_eq!(endian.read_target_uint(&buf), val);
+            }
+        }
+    }
+
+    #[test]
+    fn test_target_uint_byte_order() {
+        let mut buf = [0u8; 4];
+        Endian::Little.write_target_uint(&mut buf, 0x0102_0304);
+        assert_eq!(buf, [4, 3, 2, 1]);
+        Endian::Big.write_target_uint(&mut buf, 0x0102_0304);
+        assert_eq!(buf, [1, 2, 3, 4]);
+        assert_eq!(Endian::Little.read_target_uint(&[1, 2, 3]), 0x03_0201);
+        assert_eq!(Endian::Big.read_target_uint(&[1, 2, 3]), 0x01_0203);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_write_target_uint_overflow() {
+        let mut buf = [0u8; 2];
+        Endian::Little.write_target_uint(&mut buf, 0x1_0000);
+    }
+
+    #[test]
+    fn test_scalar_full() {
+        let dl = TargetDataLayout::default();
+        assert_eq!(Scalar::full(Int(I8, true), &dl).valid_range, 0..=0xff);
+        assert_eq!(Scalar::full(Int(I32, false), &dl).valid_range, 0..=(u32::max_value() as u128));
+        assert_eq!(Scalar::full(Int(I128, false), &dl).valid_range, 0..=u128::max_value());
+        assert_eq!(Scalar::full(Pointer, &dl).valid_range, 0..=(u64::max_value() as u128));
+    }
+
+    #[test]
+    fn test_is_register_scalar() {
+        let dl = TargetDataLayout::default();
+        let scalar = |value| Scalar::full(value, &dl);
+        assert!(Abi::Scalar(scalar(Int(I8, false))).is_register_scalar(&dl));
+        assert!(Abi::Scalar(scalar(Int(I64, false))).is_register_scalar(&dl));
+        assert!(Abi::Scalar(scalar(Pointer)).is_register_scalar(&dl));
+        assert!(!Abi::Scalar(scalar(Int(I128, false))).is_register_scalar(&dl));
+        let pair = Abi::ScalarPair(scalar(Int(I64, false)), scalar(Int(I64, false)));
+        assert!(!pair.is_register_scalar(&dl));
+        assert!(!Abi::Uninhabited.is_register_scalar(&dl));
+    }
+
+    #[test]
+    fn test_size_repeat() {
+        let dl = TargetDataLayout::default();
+        let bound = dl.obj_size_bound().unwrap();
+        assert_eq!(Size::from_bytes(4).repeat(0, &dl), Some(Size::ZERO));
+        assert_eq!(Size::from_bytes(4).repeat(3, &dl), Some(Size::from_bytes(12)));
+        assert_eq!(Size::from_bytes(1).repeat(bound - 1, &dl), Some(Size::from_bytes(bound - 1)));
+        assert_eq!(Size::from_bytes(1).repeat(bound, &dl), None);
+        assert_eq!(Size::from_bytes(bound / 2).repeat(2, &dl), None);
+        assert_eq!(Size::from_bytes(2).repeat(u64::max_value(), &dl), None);
+    }
+
+    #[test]
+    fn test_field_offsets() {
+        let placements = [
+            FieldPlacement::Union(3),
+            FieldPlacement::Array { stride: Size::from_bytes(4), count: 5 },
+            FieldPlacement::Arbitrary {
+                offsets: vec![Size::from_bytes(8), Size::ZERO, Size::from_bytes(4)],
+                memory_index: vec![2, 0, 1],
+            },
+        ];
+        for fields in &placements {
+            let offsets = fields.offsets();
+            assert_eq!(offsets.len(), fields.count());
+            for (i, &offset) in offsets.iter().enumerate() {
+                assert_eq!(offset, fields.offset(i));
+            }
+        }
+    }
+
+    #[test]
+    fn test_primitive_predicates() {
+        let dl = TargetDataLayout::default();
+        for &prim in &[
+            Int(I8, false),
+            Int(I64, true),
+            Float(FloatTy::F32),
+            Float(FloatTy::F64),
+            Pointer,
+        ] {
+            let kinds = [prim.is_int(), prim.is_float(), prim.is_ptr()];
+            assert_eq!(kinds.iter().filter(|&&is_kind| is_kind).count(), 1);
+            assert_eq!(prim.pointer_width(&dl).is_some(), prim.is_ptr());
+        }
+        assert_eq!(Pointer.pointer_width(&dl), Some(dl.pointer_size));
+    }
+
+    struct SizeOfCx;
+
+    impl LayoutOf for SizeOfCx {
+        type Ty = Integer;
+        type TyLayout = Size;
+
+        fn layout_of(&self, ty: Integer) -> Size {
+            ty.size()
+        }
+    }
+
+    #[test]
+    fn test_layout_of_many() {
+        let cx = SizeOfCx;
+        assert_eq!(cx.layout_of_many(vec![]), vec![]);
+        assert_eq!(
+            cx.layout_of_many(vec![I8, I64, I16]),
+            vec![Size::from_bytes(1), Size::from_bytes(8), Size::from_bytes(2)],
+        );
+        assert_eq!(cx.layout_of_many(Integer::all()).len(), Integer::all().count());
+    }
+
+    #[test]
+    fn test_inhabited_variants() {
+        let dl = TargetDataLayout::default();
+        let discr = Scalar::full(Int(I8, false), &dl);
+
+        // `enum E { A(u8), B(!), C }`
+        let variants = IndexVec::from_raw(vec![
+            LayoutDetails::scalar(&dl, discr.clone()),
+            uninhabited_layout(),
+            LayoutDetails::scalar(&dl, discr.clone()),
+        ]);
+        let details = LayoutDetails {
+            variants: Variants::Multiple {
+                discr: discr.clone(),
+                discr_kind: DiscriminantKind::Tag,
+                discr_index: 0,
+                variants,
+            },
+            ..LayoutDetails::scalar(&dl, discr)
+        };
+        let layout = TyLayout { ty: (), details: &details };
+        assert_eq!(layout.inhabited_variants().collect::<Vec<_>>(),
+                   [VariantIdx::new(0), VariantIdx::new(2)]);
+
+        // `enum E { A(!) }`
+        let details = uninhabited_layout();
+        let layout = TyLayout { ty: (), details: &details };
+        assert_eq!(layout.inhabited_variants().count(), 0);
+    }
+
+    #[test]
+    fn test_ffi_classification() {
+        let dl = TargetDataLayout::default();
+
+        // `#[repr(C)] struct S { a: u32, b: u64 }`
+        let details = aggregate(struct_fields(&[0, 8], &[0, 1]), align(8), 16);
+        let layout = TyLayout { ty: (), details: &details };
+        assert_eq!(layout.ffi_classification(), FfiClass::Aggregate { sized: true });
+
+        // `&[u8]`
+        let data_ptr = Scalar::full(Pointer, &dl);
+        let len = Scalar::full(Int(I64, false), &dl);
+        let details = LayoutDetails {
+            abi: Abi::ScalarPair(data_ptr, len),
+            ..aggregate(struct_fields(&[0, 8], &[0, 1]), align(8), 16)
+        };
+        let layout = TyLayout { ty: (), details: &details };
+        assert_eq!(layout.ffi_classification(), FfiClass::ScalarPair);
+
+        // `[u8]`
+        let fields = FieldPlacement::Array { stride: Size::from_bytes(1), count: 0 };
+        let details = LayoutDetails {
+            abi: Abi::Aggregate { sized: false },
+            ..aggregate(fields, align(1), 0)
+        };
+        let layout = TyLayout { ty: (), details: &details };
+        assert_eq!(layout.ffi_classification(), FfiClass::Aggregate { sized: false });
+    }
+
+    #[test]
+    fn test_ffi_scalar_pair_components() {
+        let dl = TargetDataLayout::default();
+        let scalar_pair = |a: Primitive, b: Primitive| LayoutDetails {
+            abi: Abi::ScalarPair(Scalar::full(a, &dl), Scalar::full(b, &dl)),
+            ..aggregate(
+                struct_fields(&[0, a.size(&dl).bytes()], &[0, 1]),
+                a.align(&dl).abi.stricter(b.align(&dl).abi),
+                (a.size(&dl) + b.size(&dl)).bytes(),
+            )
+        };
+
+        // `&[u8]`
+        let details = scalar_pair(Pointer, Int(I64, false));
+        let layout = TyLayout { ty: (), details: &details };
+        assert!(layout.is_ffi_scalar_pair());
+        let (a, b) = layout.ffi_scalar_pair_components().unwrap();
+        assert!(a.value.is_ptr());
+        assert!(b.value.is_int());
+
+        // `#[repr(C)] struct S { a: u32, b: u32 }`
+        let details = scalar_pair(Int(I32, false), Int(I32, false));
+        let layout = TyLayout { ty: (), details: &details };
+        assert!(layout.is_ffi_scalar_pair());
+        let (a, b) = layout.ffi_scalar_pair_components().unwrap();
+        assert!(a.value.is_int());
+        assert!(b.value.is_int());
+
+        // `u64`
+        let details = LayoutDetails::scalar(&dl, Scalar::full(Int(I64, false), &dl));
+        let layout = TyLayout { ty: (), details: &details };
+        assert!(!layout.is_ffi_scalar_pair());
+        assert!(layout.ffi_scalar_pair_components().is_none());
+    }
+
+    #[test]
+    fn test_read_discriminant_value_tag() {
+        for &endian in &[Endian::Little, Endian::Big] {
+            let dl = TargetDataLayout { endian, ..TargetDataLayout::default() };
+            let variants = enum_variants(&dl, Int(I16, false), DiscriminantKind::Tag);
+            let mut bytes = [0xff; 6];
+            endian.write_target_uint(&mut bytes[2..4], 0x0102);
+            let offset = Size::from_bytes(2);
+            assert_eq!(variants.read_discriminant_value(&bytes, offset, &dl), Some(0x0102));
+            assert_eq!(variants.read_discriminant_value(&bytes[..3], offset, &dl), None);
+        }
+
+        let dl = TargetDataLayout::default();
+        let single = Variants::Single { index: VariantIdx::new(0) };
+        assert_eq!(single.read_discriminant_value(&[0], Size::ZERO, &dl), None);
+    }
+
+    #[test]
+    fn test_read_discriminant_value_signed_tag() {
+        for &endian in &[Endian::Little, Endian::Big] {
+            let dl = TargetDataLayout { endian, ..TargetDataLayout::default() };
+            let variants = enum_variants(&dl, Int(I16, true), DiscriminantKind::Tag);
+            let read = |val| {
+                let mut bytes = [0; 2];
+                endian.write_target_uint(&mut bytes, val);
+                variants.read_discriminant_value(&bytes, Size::ZERO, &dl)
+            };
+            assert_eq!(read(0xfffe), Some(-2i128 as u128));
+            assert_eq!(read(0x8000), Some(-0x8000i128 as u128));
+            assert_eq!(read(0x7fff), Some(0x7fff));
+        }
+    }
+
+    #[test]
+    fn test_read_discriminant_value_niche() {
+        for &endian in &[Endian::Little, Endian::Big] {
+            let dl = TargetDataLayout { endian, ..TargetDataLayout::default() };
+            let read = |variants: &Variants, val| {
+                let mut bytes = [0; 2];
+                endian.write_target_uint(&mut bytes, val);
+                variants.read_discriminant_value(&bytes, Size::ZERO, &dl)
+            };
+
+            // `enum E { A(u16 in 0..=0xfffd), B, C }`: `B` is 0xfffe and `C` is 0xffff.
+            let variants = enum_variants(&dl, Int(I16, false), DiscriminantKind::Niche {
+                dataful_variant: VariantIdx::new(0),
+                niche_variants: VariantIdx::new(1)..=VariantIdx::new(2),
+                niche_start: 0xfffe,
+            });
+            assert_eq!(read(&variants, 0xfffe), Some(1));
+            assert_eq!(read(&variants, 0xffff), Some(2));
+            assert_eq!(read(&variants, 0x1234), Some(0));
+
+            // Niche values wrapping around: `B` is 0xffff and `C` is 0x0000.
+            let variants = enum_variants(&dl, Int(I16, false), DiscriminantKind::Niche {
+                dataful_variant: VariantIdx::new(0),
+                niche_variants: VariantIdx::new(1)..=VariantIdx::new(2),
+                niche_start: 0xffff,
+            });
+            assert_eq!(read(&variants, 0xffff), Some(1));
+            assert_eq!(read(&variants, 0x0000), Some(2));
+            assert_eq!(read(&variants, 0x0001), Some(0));
+        }
+    }
+
+    #[test]
+    fn test_discriminant_for_variant() {
+        let dl = TargetDataLayout::default();
+        let variant = VariantIdx::new;
+
+        let variants = enum_variants(&dl, Int(I16, false), DiscriminantKind::Tag);
+        assert_eq!(variants.discriminant_for_variant(&dl, variant(0), 0), Some(0));
+        assert_eq!(variants.discriminant_for_variant(&dl, variant(2), 2), Some(2));
+
+        // `enum E { A = 10, B = 20 }`: the tag is the explicit discriminant.
+        let variants = enum_variants(&dl, Int(I8, false), DiscriminantKind::Tag);
+        assert_eq!(variants.discriminant_for_variant(&dl, variant(1), 20), Some(20));
+        let mut bytes = [0; 1];
+        dl.endian.write_target_uint(&mut bytes, 20);
+        assert_eq!(variants.read_discriminant_value(&bytes, Size::ZERO, &dl), Some(20));
+
+        // `#[repr(i8)] enum E { A = -1 }`: only the size of the tag is stored.
+        let variants = enum_variants(&dl, Int(I8, true), DiscriminantKind::Tag);
+        let discr = variants.discriminant_for_variant(&dl, variant(0), -1i128 as u128);
+        assert_eq!(discr, Some(0xff));
+        dl.endian.write_target_uint(&mut bytes, 0xff);
+        assert_eq!(
+            variants.read_discriminant_value(&bytes, Size::ZERO, &dl),
+            Some(-1i128 as u128)
+        );
+
+        // `Option<&T>`: `None` is the null pointer.
+        let variants = enum_variants(&dl, Pointer, DiscriminantKind::Niche {
+            dataful_variant: variant(1),
+            niche_variants: variant(0)..=variant(0),
+            niche_start: 0,
+        });
+        assert_eq!(variants.discriminant_for_variant(&dl, variant(0), 0), Some(0));
+        assert_eq!(variants.discriminant_for_variant(&dl, variant(1), 1), None);
+
+        // Niche values wrapping around: `B` is 0xffff and `C` is 0x0000.
+        let variants = enum_variants(&dl, Int(I16, false), DiscriminantKind::Niche {
+            dataful_variant: variant(0),
+            niche_variants: variant(1)..=variant(2),
+            niche_start: 0xffff,
+        });
+        assert_eq!(variants.discriminant_for_variant(&dl, variant(1), 1), Some(0xffff));
+        assert_eq!(variants.discriminant_for_variant(&dl, variant(2), 2), Some(0));
+        for &val in &[0xffff, 0] {
+            let mut bytes = [0; 2];
+            dl.endian.write_target_uint(&mut bytes, val);
+            let index = variants.read_discriminant_value(&bytes, Size::ZERO, &dl).unwrap();
+            assert_eq!(
+                variants.discriminant_for_variant(&dl, variant(index as usize), index),
+                Some(val)
+            );
+        }
+
+        let single = Variants::Single { index: variant(0) };
+        assert_eq!(single.discriminant_for_variant(&dl, variant(0), 0), None);
+    }
+
+    #[test]
+    fn test_niche_offset() {
+        let dl = TargetDataLayout::default();
+        let variant = VariantIdx::new;
+        let niche = || DiscriminantKind::Niche {
+            dataful_variant: variant(1),
+            niche_variants: variant(0)..=variant(0),
+            niche_start: 0,
+        };
+        let enum_layout = |discr_kind, offset| LayoutDetails {
+            variants: enum_variants(&dl, Pointer, discr_kind),
+            fields: FieldPlacement::Arbitrary {
+                offsets: vec![offset],
+                memory_index: vec![0],
+            },
+            ..LayoutDetails::scalar(&dl, Scalar::full(Pointer, &dl))
+        };
+
+        // `Option<&T>`: the niche is the pointer itself.
+        let layout = enum_layout(niche(), Size::ZERO);
+        assert_eq!(layout.niche_offset(), Some(Size::ZERO));
+
+        // `Option<(u64, &T)>`: the niche is the pointer in the second field.
+        let layout = enum_layout(niche(), Size::from_bytes(8));
+        assert_eq!(layout.niche_offset(), Some(Size::from_bytes(8)));
+
+        let layout = enum_layout(DiscriminantKind::Tag, Size::ZERO);
+        assert_eq!(layout.niche_offset(), None);
+
+        let layout = LayoutDetails::scalar(&dl, Scalar::full(Pointer, &dl));
+        assert_eq!(layout.niche_offset(), None);
+    }
+
+    #[test]
+    fn test_smallest_covering_integer() {
+        let scalar = |value, valid_range| Scalar { value, valid_range };
+
+        assert_eq!(scalar(Int(I32, false), 0..=200).smallest_covering_integer(), Some(I8));
+        assert_eq!(scalar(Int(I32, false), 0..=256).smallest_covering_integer(), Some(I16));
+        assert_eq!(scalar(Int(I8, false), 0..=1).smallest_covering_integer(), Some(I8));
+
+        // `-2..=1` as an `i32`, which wraps around when viewed as unsigned.
+        let minus_two_to_one = 0xffff_fffe..=1;
+        assert_eq!(
+            scalar(Int(I32, true), minus_two_to_one.clone()).smallest_covering_integer(),
+            Some(I8)
+        );
+        // As a `u32`, the same range is `0xffff_fffe..=0xffff_ffff` and `0..=1`.
+        assert_eq!(
+            scalar(Int(I32, false), minus_two_to_one).smallest_covering_integer(),
+            Some(I32)
+        );
+        // `i16` values `0x7fff` and `-0x8000`, wrapping around when signed.
+        assert_eq!(
+            scalar(Int(I16, true), 0x7fff..=0x8000).smallest_covering_integer(),
+            Some(I16)
+        );
+
+        assert_eq!(scalar(Pointer, 1..=0xffff).smallest_covering_integer(), None);
+    }
+
+    #[test]
+    fn test_leak_data_layout() {
+        let dl = TargetDataLayout::default().leak();
+        let handle = std::thread::spawn(move || dl.data_layout().pointer_size);
+        assert_eq!(handle.join().unwrap(), dl.pointer_size);
+        assert_eq!(dl.vector_align(Size::from_bytes(16)).abi.bytes(), 16);
+    }
+
+    #[test]
+    fn test_const_align_to() {
+        const ALIGN: Align = Align { pow2: 3 };
+        const SIZE: Size = Size::from_bytes(13).align_to(ALIGN);
+        const IS_ALIGNED: bool = SIZE.is_aligned(ALIGN);
+        const ABI_AND_PREF: AbiAndPrefAlign = AbiAndPrefAlign::new(ALIGN);
+        assert_eq!(SIZE.bytes(), 16);
+        assert!(IS_ALIGNED);
+        assert_eq!(ABI_AND_PREF.pref.bits(), 64);
+    }
+
+    fn parse_data_layout(data_layout: &str) -> Result<TargetDataLayout, String> {
+        let triple = crate::spec::TargetTriple::from_triple("x86_64-unknown-linux-gnu");
+        let mut target = Target::search(&triple).unwrap();
+        target.data_layout = data_layout.to_string();
+        TargetDataLayout::parse(&target)
+    }
+
+    #[test]
+    fn test_parse_stack_align() {
+        let fallback = Align::from_bytes(8).unwrap();
+
+        let dl = parse_data_layout("e-m:e-i64:64-f80:128-n8:16:32:64-S128").unwrap();
+        assert_eq!(dl.stack_align, Some(Align::from_bytes(16).unwrap()));
+        assert_eq!(dl.stack_align_or(fallback).bytes(), 16);
+
+        let dl = parse_data_layout("e-m:e-i64:64-f80:128-n8:16:32:64").unwrap();
+        assert_eq!(dl.stack_align, None);
+        assert_eq!(dl.stack_align_or(fallback).bytes(), 8);
+
+        let dl = parse_data_layout("e-m:e-i64:64-S0").unwrap();
+        assert_eq!(dl.stack_align, None);
+
+        assert!(parse_data_layout("e-m:e-i64:64-S96").is_err());
+        assert!(parse_data_layout("e-m:e-i64:64-Sfoo").is_err());
+    }
+
+    #[test]
+    fn test_min_size() {
+        // `struct S { a: u32, tail: [u8] }`
+        let details = LayoutDetails {
+            abi: Abi::Aggregate { sized: false },
+            ..aggregate(struct_fields(&[0, 4], &[0, 1]), align(4), 4)
+        };
+        let layout = TyLayout { ty: (), details: &details };
+        assert!(!layout.is_exact_size());
+        assert_eq!(layout.min_size(), Size::from_bytes(4));
+
+        // `struct S { a: u32, b: u32 }`
+        let details = LayoutDetails {
+            abi: Abi::Aggregate { sized: true },
+            size: Size::from_bytes(8),
+            ..details
+        };
+        let layout = TyLayout { ty: (), details: &details };
+        assert!(layout.is_exact_size());
+        assert_eq!(layout.min_size(), Size::from_bytes(8));
+    }
+
+    #[test]
+    fn test_scalar_range_contains() {
+        let dl = TargetDataLayout::default();
+        let scalar = |value, range: RangeInclusive<u128>| Scalar { value, valid_range: range };
+
+        let u8_full = Scalar::full(Int(I8, false), &dl);
+        let bool_ = scalar(Int(I8, false), 0..=1);
+        assert!(bool_.is_bool());
+        assert!(u8_full.same_primitive(&bool_));
+        assert_ne!(u8_full, bool_);
+        assert!(u8_full.range_contains(&bool_, &dl));
+        assert!(!bool_.range_contains(&u8_full, &dl));
+        assert!(bool_.range_contains(&bool_, &dl));
+        assert!(!u8_full.same_primitive(&Scalar::full(Int(I8, true), &dl)));
+
+        // `254..=2` wraps around: 254, 255, 0, 1, 2.
+        let wrapping = scalar(Int(I8, false), 254..=2);
+        assert!(u8_full.range_contains(&wrapping, &dl));
+        assert!(!wrapping.range_contains(&u8_full, &dl));
+        assert!(wrapping.range_contains(&bool_, &dl));
+        assert!(wrapping.range_contains(&scalar(Int(I8, false), 255..=1), &dl));
+        assert!(wrapping.range_contains(&scalar(Int(I8, false), 254..=255), &dl));
+        assert!(!wrapping.range_contains(&scalar(Int(I8, false), 253..=0), &dl));
+        assert!(!wrapping.range_contains(&scalar(Int(I8, false), 2..=3), &dl));
+        assert!(!bool_.range_contains(&wrapping, &dl));
+
+        // A wrapping range with no gap is the full range.
+        let wrapping_full = scalar(Int(I8, false), 128..=127);
+        assert!(wrapping_full.range_contains(&u8_full, &dl));
+        assert!(wrapping_full.range_contains(&wrapping, &dl));
+    }
+
+    #[test]
+    fn test_obj_size_bound() {
+        let dl = TargetDataLayout::default();
+        assert_eq!(dl.obj_size_bound(), Some(1 << 47));
+        assert_eq!(Size::from_bytes(1 << 46).checked_add(Size::from_bytes(1 << 46), &dl), None);
+
+        // An override takes precedence over the pointer size.
+        let dl = TargetDataLayout { max_object_size: Some(1 << 20), ..TargetDataLayout::default() };
+        assert_eq!(dl.obj_size_bound(), Some(1 << 20));
+        assert_eq!(Size::from_bytes(1 << 19).checked_mul(2, &dl), None);
+        assert_eq!(
+            Size::from_bytes(1 << 19).checked_add(Size::from_bytes(1), &dl),
+            Some(Size::from_bytes((1 << 19) + 1)),
+        );
+
+        // No bound is known for 24-bit pointers, so no size computation succeeds.
+        let dl = TargetDataLayout {
+            pointer_size: Size::from_bits(24),
+            ..TargetDataLayout::default()
+        };
+        assert_eq!(dl.obj_size_bound(), None);
+        assert_eq!(Size::from_bytes(1).checked_add(Size::from_bytes(1), &dl), None);
+
+        let dl = TargetDataLayout {
+            pointer_size: Size::from_bits(24),
+            max_object_size: Some(1 << 23),
+            ..TargetDataLayout::default()
+        };
+        assert_eq!(dl.obj_size_bound(), Some(1 << 23));
+
+        let dl = TargetDataLayout { max_object_size: Some(1 << 61), ..TargetDataLayout::default() };
+        assert_eq!(dl.obj_size_bound(), Some(1 << 61));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_obj_size_bound_too_large() {
+        let dl = TargetDataLayout {
+            max_object_size: Some((1 << 61) + 1),
+            ..TargetDataLayout::default()
+        };
+        dl.obj_size_bound();
+    }
+
+    #[test]
+    fn test_parse_unknown_pointer_width() {
+        let triple = crate::spec::TargetTriple::from_triple("x86_64-unknown-linux-gnu");
+        let mut target = Target::search(&triple).unwrap();
+        target.data_layout = "e-p:24:8".to_string();
+        target.target_pointer_width = "24".to_string();
+        let err = TargetDataLayout::parse(&target).unwrap_err();
+        assert!(err.contains("not known for 24-bit pointers"), "{}", err);
+    }
+
+    #[test]
+    fn test_uninhabited_field_indices() {
+        let cx = &FieldTestCx::new(&TargetDataLayout::default());
+
+        // `struct S { a: u32, b: !, c: u32 }`
+        let details = LayoutDetails {
+            abi: Abi::Uninhabited,
+            ..aggregate(struct_fields(&[0, 4, 4], &[0, 1, 2]), align(4), 8)
+        };
+        let ty = FieldTestTy::Aggregate(&[FieldTestTy::U32, FieldTestTy::Never, FieldTestTy::U32]);
+        let layout = TyLayout { ty, details: &details };
+        assert_eq!(layout.uninhabited_field_indices(&cx), vec![1]);
+
+        // `union U { a: !, b: ! }`
+        let details = LayoutDetails {
+            fields: FieldPlacement::Union(2),
+            size: Size::ZERO,
+            ..details
+        };
+        let ty = FieldTestTy::Aggregate(&[FieldTestTy::Never, FieldTestTy::Never]);
+        let layout = TyLayout { ty, details: &details };
+        assert_eq!(layout.uninhabited_field_indices(&cx), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn test_fields_with_layouts() {
+        let cx = &FieldTestCx::new(&TargetDataLayout::default());
+
+        // `struct S { a: u8, b: u64, c: u32 }`, reordered to `b, c, a`.
+        let details = aggregate(struct_fields(&[12, 0, 8], &[2, 0, 1]), align(8), 16);
+        let ty = FieldTestTy::Aggregate(&[FieldTestTy::U8, FieldTestTy::U64, FieldTestTy::U32]);
+        let layout = TyLayout { ty, details: &details };
+
+        let fields = layout.fields_with_layouts(&cx);
+        assert_eq!(fields.len(), 3);
+        for (i, offset, field) in fields {
+            assert_eq!(offset, layout.fields.offset(i));
+            assert_eq!(field.details, layout.field(&cx, i).details);
+        }
+        let sizes: Vec<_> = layout.fields_with_layouts(&cx).map(|(_, _, f)| f.size).collect();
+        assert_eq!(sizes, [Size::from_bytes(1), Size::from_bytes(8), Size::from_bytes(4)]);
+
+        // Field layouts are computed lazily: the layout of the nested
+        // aggregate, which `FieldTestCx` cannot compute, is never requested.
+        let details = aggregate(struct_fields(&[0, 8], &[0, 1]), align(8), 16);
+        let ty = FieldTestTy::Aggregate(&[FieldTestTy::U8, FieldTestTy::Aggregate(&[])]);
+        let layout = TyLayout { ty, details: &details };
+        let mut fields = layout.fields_with_layouts(&cx);
+        let (i, offset, field) = fields.next().unwrap();
+        assert_eq!((i, offset, field.size), (0, Size::ZERO, Size::from_bytes(1)));
+        assert_eq!(fields.len(), 1);
+    }
+
+    #[test]
+    fn test_variant_size() {
+        let dl = TargetDataLayout::default();
+        let variant = |index, align, size| LayoutDetails {
+            variants: Variants::Single { index: VariantIdx::new(index) },
+            ..aggregate(struct_fields(&[], &[]), align, size)
+        };
+
+        // `enum E { Small(u8), Large([u64; 25]) }`
+        let mut variants = enum_variants(&dl, Int(I8, false), DiscriminantKind::Tag);
+        if let Variants::Multiple { variants: ref mut v, .. } = variants {
+            *v = IndexVec::from_raw(vec![variant(0, align(1), 2), variant(1, align(8), 208)]);
+        }
+        let details = LayoutDetails {
+            variants,
+            ..aggregate(struct_fields(&[0], &[0]), align(8), 208)
+        };
+        assert_eq!(details.variant_size(VariantIdx::new(0)), Some(Size::from_bytes(2)));
+        assert_eq!(details.variant_size(VariantIdx::new(1)), Some(Size::from_bytes(208)));
+        assert_eq!(details.variant_size(VariantIdx::new(2)), None);
+        assert_eq!(details.variant_align(VariantIdx::new(0)), Some(AbiAndPrefAlign::new(align(1))));
+        assert_eq!(details.variant_align(VariantIdx::new(1)), Some(AbiAndPrefAlign::new(align(8))));
+
+        let single = variant(0, align(4), 16);
+        assert_eq!(single.variant_size(VariantIdx::new(0)), Some(Size::from_bytes(16)));
+        assert_eq!(single.variant_align(VariantIdx::new(0)), Some(AbiAndPrefAlign::new(align(4))));
+        assert_eq!(single.variant_size(VariantIdx::new(1)), None);
+    }
+
+    #[test]
+    fn test_fields_in_memory_order() {
+        let bytes = Size::from_bytes;
+
+        // `struct S { a: u8, b: u32, c: u16 }`, reordered to `b, c, a`.
+        let fields = FieldPlacement::Arbitrary {
+            offsets: vec![bytes(6), bytes(0), bytes(4)],
+            memory_index: vec![2, 0, 1],
+        };
+        assert_eq!(
+            fields.fields_in_memory_order().collect::<Vec<_>>(),
+            [(1, bytes(0)), (2, bytes(4)), (0, bytes(6))]
+        );
+
+        let fields = FieldPlacement::Array { stride: bytes(4), count: 3 };
+        assert_eq!(
+            fields.fields_in_memory_order().collect::<Vec<_>>(),
+            [(0, bytes(0)), (1, bytes(4)), (2, bytes(8))]
+        );
+
+        let fields = FieldPlacement::Union(2);
+        assert_eq!(
+            fields.fields_in_memory_order().collect::<Vec<_>>(),
+            [(0, Size::ZERO), (1, Size::ZERO)]
+        );
+    }
+
+    #[test]
+    fn test_size_in_bits_checked() {
+        let details = |bytes| LayoutDetails {
+            size: Size::from_bytes(bytes),
+            ..LayoutDetails::scalar(&TargetDataLayout::default(), Scalar {
+                value: Int(I8, false),
+                valid_range: 0..=255,
+            })
+        };
+
+        assert_eq!(details(4).size_in_bits_checked(), Some(32));
+        assert_eq!(details((1 << 61) - 1).size_in_bits_checked(), Some(u64::max_value() - 7));
+        assert_eq!(details(1 << 61).size_in_bits_checked(), None);
+        assert_eq!(details(u64::max_value()).size_in_bits_checked(), None);
+    }
+
+    #[test]
+    fn test_scalar_pair_components() {
+        let dl = TargetDataLayout::default();
+
+        // `&[u8]`
+        let data_ptr = Scalar::full(Pointer, &dl);
+        let len = Scalar::full(Int(I64, false), &dl);
+        let details = LayoutDetails {
+            abi: Abi::ScalarPair(data_ptr.clone(), len.clone()),
+            ..aggregate(struct_fields(&[0, 8], &[0, 1]), align(8), 16)
+        };
+        let layout = TyLayout { ty: (), details: &details };
+        let (a, b, b_offset) = layout.scalar_pair_components(&dl).unwrap();
+        assert_eq!(a.abi, Abi::Scalar(data_ptr));
+        assert_eq!(a.size, dl.pointer_size);
+        assert_eq!(b.abi, Abi::Scalar(len));
+        assert_eq!(b.size, Size::from_bytes(8));
+        assert_eq!(b_offset, Size::from_bytes(8));
+        assert_eq!(b_offset, layout.fields.offset(1));
+
+        // `(u8, u64)`, with `u64` only 4-byte aligned by default, `(u32, u16)`
+        // and `(u8, u8)`.
+        for &(a, b, b_offset) in &[(I8, I64, 4), (I32, I16, 4), (I8, I8, 1)] {
+            let a = Scalar::full(Int(a, false), &dl);
+            let b = Scalar::full(Int(b, false), &dl);
+            let details = LayoutDetails {
+                abi: Abi::ScalarPair(a, b),
+                ..aggregate(struct_fields(&[0, b_offset], &[0, 1]), align(8), 16)
+            };
+            let layout = TyLayout { ty: (), details: &details };
+            let (_, _, offset) = layout.scalar_pair_components(&dl).unwrap();
+            assert_eq!(offset, Size::from_bytes(b_offset));
+        }
+
+        let details = LayoutDetails::scalar(&dl, Scalar::full(Pointer, &dl));
+        let layout = TyLayout { ty: (), details: &details };
+        assert!(layout.scalar_pair_components(&dl).is_none());
+    }
+
+    #[test]
+    fn test_data_layout_string_round_trip() {
+        let parse = |data_layout: &str, endian: &str, pointer_width: &str| {
+            let triple = crate::spec::TargetTriple::from_triple("x86_64-unknown-linux-gnu");
+            let mut target = Target::search(&triple).unwrap();
+            target.data_layout = data_layout.to_string();
+            target.target_endian = endian.to_string();
+            target.target_pointer_width = pointer_width.to_string();
+            TargetDataLayout::parse(&target).unwrap()
+        };
+
+        for &(data_layout, endian, pointer_width) in &[
+            // x86_64-unknown-linux-gnu
+            ("e-m:e-i64:64-f80:128-n8:16:32:64-S128", "little", "64"),
+            // aarch64-unknown-linux-gnu
+            ("e-m:e-i8:8:32-i16:16:32-i64:64-i128:128-n32:64-S128", "little", "64"),
+            // i686-unknown-linux-gnu
+            ("e-m:e-p:32:32-f64:32:64-f80:32-n8:16:32-S128", "little", "32"),
+            // powerpc64-unknown-linux-gnu
+            ("E-m:e-i64:64-n32:64", "big", "64"),
+            // avr-unknown-unknown
+            ("e-P1-p:16:8-i8:8-i16:8-i32:8-i64:8-f32:8-f64:8-n8-a:8", "little", "16"),
+        ] {
+            let dl = parse(data_layout, endian, pointer_width);
+            let rendered = dl.to_data_layout_string();
+            let reparsed = parse(&rendered, endian, pointer_width);
+            assert_eq!(reparsed.to_data_layout_string(), rendered, "{}", data_layout);
+
+            assert!(reparsed.endian == dl.endian);
+            assert_eq!(reparsed.pointer_size, dl.pointer_size);
+            assert_eq!(reparsed.pointer_align, dl.pointer_align);
+            assert_eq!(reparsed.i64_align, dl.i64_align);
+            assert_eq!(reparsed.i128_align, dl.i128_align);
+            assert_eq!(reparsed.f64_align, dl.f64_align);
+            assert_eq!(reparsed.aggregate_align, dl.aggregate_align);
+            assert_eq!(reparsed.vector_align, dl.vector_align);
+            assert_eq!(reparsed.stack_align, dl.stack_align);
+            assert_eq!(reparsed.instruction_address_space, dl.instruction_address_space);
+        }
+
+        let dl = parse("e-m:e-i64:64-f80:128-n8:16:32:64-S128", "little", "64");
+        assert_eq!(
+            dl.to_data_layout_string(),
+            "e-p:64:64:64-i1:8:8-i8:8:8-i16:16:16-i32:32:32-i64:64:64-i128:64:64-\
+             f32:32:32-f64:64:64-v64:64:64-v128:128:128-a:8:64-S128"
+        );
+    }
+
+    #[test]
+    fn test_max_field_align() {
+        let dl = TargetDataLayout {
+            i64_align: AbiAndPrefAlign::new(align(8)),
+            ..TargetDataLayout::default()
+        };
+        let cx = &FieldTestCx::new(&dl);
+
+        // `struct S { a: u8, b: u64, c: u8 }`
+        let details = aggregate(struct_fields(&[0, 8, 1], &[0, 2, 1]), align(8), 16);
+        let ty = FieldTestTy::Aggregate(&[FieldTestTy::U8, FieldTestTy::U64, FieldTestTy::U8]);
+        let layout = TyLayout { ty, details: &details };
+        assert_eq!(layout.max_field_align(&cx), align(8));
+
+        // `#[repr(align(16))] struct E;`
+        let details = aggregate(struct_fields(&[], &[]), align(16), 0);
+        let layout = TyLayout { ty: FieldTestTy::Aggregate(&[]), details: &details };
+        assert_eq!(layout.max_field_align(&cx), align(16));
+    }
+
+    #[test]
+    fn test_homogeneous_scalar_elements() {
+        let cx = &FieldTestCx::new(&TargetDataLayout::default());
+        let f32_scalar = Scalar::full(Float(FloatTy::F32), &TargetDataLayout::default());
+
+        // `[f32; 4]`
+        let stride = Size::from_bytes(4);
+        let details = aggregate(FieldPlacement::Array { stride, count: 4 }, align(4), 16);
+        let ty = FieldTestTy::Aggregate(&[FieldTestTy::F32]);
+        let layout = TyLayout { ty, details: &details };
+        assert_eq!(layout.homogeneous_scalar_elements(&cx), Some((f32_scalar.clone(), 4)));
+
+        // `struct S { a: f32, b: f32 }`
+        let details = aggregate(struct_fields(&[0, 4], &[0, 1]), align(4), 8);
+        let ty = FieldTestTy::Aggregate(&[FieldTestTy::F32, FieldTestTy::F32]);
+        let layout = TyLayout { ty, details: &details };
+        assert_eq!(layout.homogeneous_scalar_elements(&cx), Some((f32_scalar, 2)));
+
+        // `(f32, u32)`
+        let ty = FieldTestTy::Aggregate(&[FieldTestTy::F32, FieldTestTy::U32]);
+        let layout = TyLayout { ty, details: &details };
+        assert_eq!(layout.homogeneous_scalar_elements(&cx), None);
+
+        // `#[repr(C, align(16))] struct S { a: f32, b: f32 }`, with trailing padding.
+        let details = aggregate(struct_fields(&[0, 4], &[0, 1]), align(4), 16);
+        let ty = FieldTestTy::Aggregate(&[FieldTestTy::F32, FieldTestTy::F32]);
+        let layout = TyLayout { ty, details: &details };
+        assert_eq!(layout.homogeneous_scalar_elements(&cx), None);
+    }
+
+    #[test]
+    fn test_align_from_bytes_clamped() {
+        assert_eq!(Align::from_bytes_clamped(0).bytes(), 1);
+        assert_eq!(Align::from_bytes_clamped(8), Align::from_bytes(8).unwrap());
+        assert_eq!(Align::from_bytes_clamped(3).bytes(), 4);
+        assert_eq!(Align::from_bytes_clamped(9).bytes(), 16);
+        assert_eq!(Align::from_bytes_clamped(1 << 29).bytes(), 1 << 29);
+        assert_eq!(Align::from_bytes_clamped((1 << 29) + 1).bytes(), 1 << 29);
+        assert_eq!(Align::from_bytes_clamped(u64::max_value()).bytes(), 1 << 29);
+    }
+
+    #[test]
+    fn test_data_layout_is_abi_compatible() {
+        let dl = TargetDataLayout::default();
+        assert!(dl.is_abi_compatible(&TargetDataLayout::default()));
+
+        let narrow_pointers = TargetDataLayout {
+            pointer_size: Size::from_bits(32),
+            ..TargetDataLayout::default()
+        };
+        assert!(!dl.is_abi_compatible(&narrow_pointers));
+
+        let i64_abi_align = TargetDataLayout {
+            i64_align: AbiAndPrefAlign::new(Align::from_bits(64).unwrap()),
+            ..TargetDataLayout::default()
+        };
+        assert!(!dl.is_abi_compatible(&i64_abi_align));
+
+        let aggregate_pref_align = TargetDataLayout {
+            aggregate_align: AbiAndPrefAlign {
+                abi: dl.aggregate_align.abi,
+                pref: Align::from_bits(128).unwrap(),
+            },
+            ..TargetDataLayout::default()
+        };
+        assert!(dl.is_abi_compatible(&aggregate_pref_align));
+    }
+
+    #[test]
+    fn test_field_placement_has_padding() {
+        let dl = TargetDataLayout::default();
+        let int = |int| LayoutDetails::scalar(&dl, Scalar::full(Int(int, false), &dl));
+        let bytes = Size::from_bytes;
+
+        // `#[repr(C)] struct S { a: u32, b: u32 }`
+        let fields = FieldPlacement::Arbitrary {
+            offsets: vec![bytes(0), bytes(4)],
+            memory_index: vec![0, 1],
+        };
+        assert!(!fields.has_padding(&[int(I32), int(I32)], bytes(8)));
+
+        // `struct S(u8, u64)`, with `u64` aligned to 8 bytes.
+        let fields = FieldPlacement::Arbitrary {
+            offsets: vec![bytes(8), bytes(0)],
+            memory_index: vec![1, 0],
+        };
+        assert!(fields.has_padding(&[int(I8), int(I64)], bytes(16)));
+
+        let fields = FieldPlacement::Array { stride: bytes(4), count: 2 };
+        assert!(!fields.has_padding(&[int(I32)], bytes(8)));
+        assert!(fields.has_padding(&[int(I8)], bytes(8)));
+
+        let fields = FieldPlacement::Union(2);
+        assert!(!fields.has_padding(&[int(I8), int(I32)], bytes(4)));
+        assert!(fields.has_padding(&[int(I8), int(I32)], bytes(8)));
+    }
+
+    #[test]
+    fn test_integer_all() {
+        assert_eq!(Integer::all().collect::<Vec<_>>(), [I8, I16, I32, I64, I128]);
+        assert_eq!(Integer::all_descending().collect::<Vec<_>>(), [I128, I64, I32, I16, I8]);
+    }
+}
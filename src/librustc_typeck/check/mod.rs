@@ -857,6 +857,7 @@ fn typeck_tables_of<'tcx>(tcx: TyCtxt<'tcx, 'tcx>, def_id: DefId) -> &'tcx ty::T
             let revealed_ty = if tcx.features().impl_trait_in_bindings {
                 fcx.instantiate_opaque_types_from_value(
                     id,
+                    body.value.span,
                     &expected_type
                 )
             } else {
@@ -963,6 +964,7 @@ impl<'a, 'gcx, 'tcx> Visitor<'gcx> for GatherLocalsVisitor<'a, 'gcx, 'tcx> {
                 let revealed_ty = if self.fcx.tcx.features().impl_trait_in_bindings {
                     self.fcx.instantiate_opaque_types_from_value(
                         self.parent_id,
+                        ty.span,
                         &o_ty
                     )
                 } else {
@@ -1054,7 +1056,11 @@ fn check_fn<'a, 'gcx, 'tcx>(inherited: &'a Inherited<'a, 'gcx, 'tcx>,
 
     let declared_ret_ty = fn_sig.output();
     fcx.require_type_is_sized(declared_ret_ty, decl.output.span(), traits::SizedReturnType);
-    let revealed_ret_ty = fcx.instantiate_opaque_types_from_value(fn_id, &declared_ret_ty);
+    let revealed_ret_ty = fcx.instantiate_opaque_types_from_value(
+        fn_id,
+        decl.output.span(),
+        &declared_ret_ty,
+    );
     fcx.ret_coercion = Some(RefCell::new(CoerceMany::new(revealed_ret_ty)));
     fn_sig = fcx.tcx.mk_fn_sig(
         fn_sig.inputs().iter().cloned(),
@@ -2395,9 +2401,16 @@ impl<'a, 'gcx, 'tcx> FnCtxt<'a, 'gcx, 'tcx> {
     /// Replaces the opaque types from the given value with type variables,
     /// and records the `OpaqueTypeMap` for later use during writeback. See
     /// `InferCtxt::instantiate_opaque_types` for more details.
+    ///
+    /// With `impl_trait_in_bindings`, the same opaque type can be
+    /// instantiated more than once in a body, e.g., in the return type and
+    /// in the type of a local. Those instantiations are merged, which
+    /// equates their hidden types; `span` is used to report the error if
+    /// they were instantiated with different generic arguments.
     fn instantiate_opaque_types_from_value<T: TypeFoldable<'tcx>>(
         &self,
         parent_id: hir::HirId,
+        span: Span,
         value: &T,
     ) -> T {
         let parent_def_id = self.tcx.hir().local_def_id_from_hir_id(parent_id);
@@ -2414,10 +2427,25 @@ impl<'a, 'gcx, 'tcx> FnCtxt<'a, 'gcx, 'tcx> {
             )
         );
 
-        let mut opaque_types = self.opaque_types.borrow_mut();
-        for (ty, decl) in opaque_type_map {
-            let old_value = opaque_types.insert(ty, decl);
-            assert!(old_value.is_none(), "instantiated twice: {:?}/{:?}", ty, decl);
+        let cause = traits::ObligationCause::misc(span, self.body_id);
+        let merged = self.merge_opaque_type_maps(
+            &cause,
+            self.param_env,
+            &mut self.opaque_types.borrow_mut(),
+            opaque_type_map,
+        );
+        match merged {
+            Ok(ok) => self.register_infer_ok_obligations(ok),
+            Err(def_id) => {
+                self.tcx.sess.span_err(
+                    span,
+                    &format!(
+                        "existential type `{}` is used with different generic arguments \
+                         in the same body",
+                        self.tcx.def_path_str(def_id),
+                    ),
+                );
+            }
         }
 
         value
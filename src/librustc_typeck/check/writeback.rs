@@ -58,6 +58,9 @@ impl<'a, 'gcx, 'tcx> FnCtxt<'a, 'gcx, 'tcx> {
         wbcx.visit_liberated_fn_sigs();
         wbcx.visit_fru_field_types();
         wbcx.visit_opaque_types(body.value.span);
+        if self.tcx.sess.opts.debugging_opts.dump_opaque_types {
+            wbcx.dump_opaque_types(item_def_id);
+        }
         wbcx.visit_coercion_casts();
         wbcx.visit_free_region_map();
         wbcx.visit_user_provided_tys();
@@ -624,6 +627,60 @@ impl<'cx, 'gcx, 'tcx> WritebackCx<'cx, 'gcx, 'tcx> {
         }
     }
 
+    /// Prints the opaque types instantiated in the body of `item_def_id`, for
+    /// `-Z dump-opaque-types`. Entries are sorted by path, as the map is unordered.
+    fn dump_opaque_types(&self, item_def_id: DefId) {
+        let opaque_types = self.fcx.opaque_types.borrow();
+        if opaque_types.is_empty() {
+            return;
+        }
+
+        let tcx = self.tcx();
+        let mut entries: Vec<_> = opaque_types
+            .iter()
+            .map(|(&def_id, opaque_defn)| (tcx.def_path_str(def_id), def_id, opaque_defn))
+            .collect();
+        entries.sort_by(|(path1, ..), (path2, ..)| path1.cmp(path2));
+
+        println!("dump-opaque-types body: `{}`", tcx.def_path_str(item_def_id));
+        for (path, def_id, opaque_defn) in entries {
+            let concrete_ty = self.fcx.resolve_vars_if_possible(&opaque_defn.concrete_ty);
+            println!(
+                "dump-opaque-types     opaque type `{}`: substs: {:?}, concrete type: `{}`, \
+                 has required region bounds: {}, origin: {:?}",
+                path,
+                opaque_defn.substs,
+                concrete_ty,
+                opaque_defn.has_required_region_bounds,
+                opaque_defn.origin,
+            );
+
+            // The definition type, written by `visit_opaque_types`, is in
+            // terms of the opaque type's own generics.
+            if let Some(resolved) = self.tables.concrete_existential_types.get(&def_id) {
+                let captured_regions: Vec<_> = self.fcx
+                    .opaque_captured_regions(resolved.concrete_type)
+                    .iter()
+                    .map(|r| r.to_string())
+                    .collect();
+                println!(
+                    "dump-opaque-types         captured regions: [{}]",
+                    captured_regions.join(", "),
+                );
+            }
+            println!(
+                "dump-opaque-types         async fn return: {}",
+                tcx.is_async_fn_opaque(def_id),
+            );
+        }
+
+        if let ty::FnDef(..) = tcx.type_of(item_def_id).sty {
+            let fn_sig = tcx.liberate_late_bound_regions(item_def_id, &tcx.fn_sig(item_def_id));
+            let expanded_ty = self.fcx.expand_opaque_types(&fn_sig.output(), &opaque_types);
+            println!("dump-opaque-types     expanded return type: `{}`", expanded_ty);
+        }
+    }
+
     fn visit_field_id(&mut self, hir_id: hir::HirId) {
         if let Some(index) = self.fcx
             .tables
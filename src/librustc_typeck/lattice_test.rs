@@ -0,0 +1,135 @@
+//! Support for `#[rustc_lattice(..)]`, which runs the lattice operations
+//! of the inference context on the argument types of a function and
+//! reports the results as errors. The `..` are the names of the
+//! operations to run.
+
+use rustc::hir;
+use rustc::hir::def_id::DefId;
+use rustc::hir::itemlikevisit::ItemLikeVisitor;
+use rustc::infer::{InferCtxt, InferOk};
+use rustc::traits::ObligationCause;
+use rustc::ty::TyCtxt;
+use syntax::ast::Attribute;
+use syntax::symbol::sym;
+
+pub fn test_lattice<'tcx>(tcx: TyCtxt<'tcx, 'tcx>) {
+    tcx.hir()
+       .krate()
+       .visit_all_item_likes(&mut LatticeTest { tcx });
+}
+
+struct LatticeTest<'tcx> {
+    tcx: TyCtxt<'tcx, 'tcx>,
+}
+
+impl ItemLikeVisitor<'tcx> for LatticeTest<'tcx> {
+    fn visit_item(&mut self, item: &'tcx hir::Item) {
+        let item_def_id = self.tcx.hir().local_def_id_from_hir_id(item.hir_id);
+
+        if let hir::ItemKind::Fn(..) = item.node {
+            for attr in self.tcx.get_attrs(item_def_id).iter() {
+                if attr.check_name(sym::rustc_lattice) {
+                    self.test_fn(item_def_id, attr);
+                }
+            }
+        }
+    }
+
+    fn visit_trait_item(&mut self, _: &'tcx hir::TraitItem) {}
+    fn visit_impl_item(&mut self, _: &'tcx hir::ImplItem) {}
+}
+
+impl LatticeTest<'tcx> {
+    fn test_fn(&self, def_id: DefId, attr: &Attribute) {
+        let tcx = self.tcx;
+        let span = tcx.def_span(def_id);
+        let param_env = tcx.param_env(def_id);
+        let sig = tcx.liberate_late_bound_regions(def_id, &tcx.fn_sig(def_id));
+        let inputs = sig.inputs();
+        let hir_id = tcx.hir().as_local_hir_id(def_id).unwrap();
+
+        for meta_item in attr.meta_item_list().unwrap_or_default() {
+            match meta_item.name_or_empty() {
+                sym::glb_n => {
+                    tcx.infer_ctxt().enter(|infcx| {
+                        let cause = ObligationCause::misc(span, hir_id);
+                        let msg = match infcx.at(&cause, param_env).glb_n(inputs) {
+                            Ok(InferOk { value, .. }) => {
+                                format!("glb_n: {} ({})", value, constraint_count(&infcx))
+                            }
+                            Err(err) => format!("glb_n: {}", err),
+                        };
+                        tcx.sess.span_err(span, &msg);
+                    });
+
+                    // For comparison, the result of folding `glb` pairwise.
+                    tcx.infer_ctxt().enter(|infcx| {
+                        let cause = ObligationCause::misc(span, hir_id);
+                        let fold = inputs.split_first().map(|(&first, rest)| {
+                            rest.iter().try_fold(first, |glb, &ty| {
+                                infcx.at(&cause, param_env).glb(glb, ty).map(|ok| ok.value)
+                            })
+                        });
+                        if let Some(Ok(value)) = fold {
+                            let count = constraint_count(&infcx);
+                            let msg = format!("pairwise glb: {} ({})", value, count);
+                            tcx.sess.span_err(span, &msg);
+                        }
+                    });
+                }
+
+                sym::glb_lub => {
+                    let (a, b) = match inputs {
+                        &[a, b] => (a, b),
+                        _ => {
+                            tcx.sess.span_err(span, "glb_lub: expected two arguments");
+                            continue;
+                        }
+                    };
+                    tcx.infer_ctxt().enter(|infcx| {
+                        let cause = ObligationCause::misc(span, hir_id);
+                        let msg = match infcx.at(&cause, param_env).glb_lub(a, b) {
+                            Ok(InferOk { value: (glb, lub), .. }) => {
+                                let count = constraint_count(&infcx);
+                                format!("glb_lub: ({}, {}) ({})", glb, lub, count)
+                            }
+                            Err(err) => format!("glb_lub: {} ({})", err, constraint_count(&infcx)),
+                        };
+                        tcx.sess.span_err(span, &msg);
+                    });
+                }
+
+                sym::try_glb => {
+                    let (a, b) = match inputs {
+                        &[a, b] => (a, b),
+                        _ => {
+                            tcx.sess.span_err(span, "try_glb: expected two arguments");
+                            continue;
+                        }
+                    };
+                    tcx.infer_ctxt().enter(|infcx| {
+                        let cause = ObligationCause::misc(span, hir_id);
+                        let glb = match infcx.try_glb(&cause, param_env, a, b) {
+                            Some((glb, _)) => glb.to_string(),
+                            None => "none".to_string(),
+                        };
+                        let count = constraint_count(&infcx);
+                        tcx.sess.span_err(span, &format!("try_glb: {} ({})", glb, count));
+                    });
+                }
+
+                name => {
+                    tcx.sess.span_err(
+                        meta_item.span(),
+                        &format!("unrecognized field name `{}`", name),
+                    );
+                }
+            }
+        }
+    }
+}
+
+fn constraint_count(infcx: &InferCtxt<'_, '_, '_>) -> String {
+    let count = infcx.with_region_constraints(|data| data.constraints.len());
+    format!("{} region constraint{}", count, if count == 1 { "" } else { "s" })
+}
@@ -93,7 +93,9 @@ mod collect;
 mod constrained_generic_params;
 mod structured_errors;
 mod impl_wf_check;
+mod lattice_test;
 mod namespace;
+mod opaque_types_test;
 mod outlives;
 mod variance;
 
@@ -335,6 +337,20 @@ pub fn check_crate<'tcx>(tcx: TyCtxt<'tcx, 'tcx>) -> Result<(), ErrorReported> {
         })?;
     }
 
+    if tcx.features().rustc_attrs {
+        tcx.sess.track_errors(|| {
+            time(tcx.sess, "lattice testing", ||
+                lattice_test::test_lattice(tcx));
+        })?;
+    }
+
+    if tcx.features().rustc_attrs {
+        tcx.sess.track_errors(|| {
+            time(tcx.sess, "opaque types testing", ||
+                opaque_types_test::test_opaque_types(tcx));
+        })?;
+    }
+
     tcx.sess.track_errors(|| {
         time(tcx.sess, "impl wf inference", ||
              impl_wf_check::impl_wf_check(tcx));
@@ -0,0 +1,168 @@
+//! Support for `#[rustc_opaque_types(..)]`, which instantiates the opaque
+//! types in the return type of a function in a fresh inference context and
+//! reports what happened as errors. The `..` are the names of the
+//! operations to run.
+
+use rustc::hir;
+use rustc::hir::def_id::DefId;
+use rustc::hir::itemlikevisit::ItemLikeVisitor;
+use rustc::infer::InferOk;
+use rustc::infer::outlives::env::OutlivesEnvironment;
+use rustc::traits::{Obligation, ObligationCause};
+use rustc::ty::{self, ToPredicate, TyCtxt};
+use syntax::ast::Attribute;
+use syntax::symbol::sym;
+
+pub fn test_opaque_types<'tcx>(tcx: TyCtxt<'tcx, 'tcx>) {
+    tcx.hir()
+       .krate()
+       .visit_all_item_likes(&mut OpaqueTypesTest { tcx });
+}
+
+struct OpaqueTypesTest<'tcx> {
+    tcx: TyCtxt<'tcx, 'tcx>,
+}
+
+impl ItemLikeVisitor<'tcx> for OpaqueTypesTest<'tcx> {
+    fn visit_item(&mut self, item: &'tcx hir::Item) {
+        let item_def_id = self.tcx.hir().local_def_id_from_hir_id(item.hir_id);
+
+        if let hir::ItemKind::Fn(..) = item.node {
+            for attr in self.tcx.get_attrs(item_def_id).iter() {
+                if attr.check_name(sym::rustc_opaque_types) {
+                    self.test_fn(item_def_id, attr);
+                }
+            }
+        }
+    }
+
+    fn visit_trait_item(&mut self, _: &'tcx hir::TraitItem) {}
+    fn visit_impl_item(&mut self, _: &'tcx hir::ImplItem) {}
+}
+
+impl OpaqueTypesTest<'tcx> {
+    fn test_fn(&self, def_id: DefId, attr: &Attribute) {
+        let tcx = self.tcx;
+        let span = tcx.def_span(def_id);
+        let param_env = tcx.param_env(def_id);
+        let sig = tcx.liberate_late_bound_regions(def_id, &tcx.fn_sig(def_id));
+        let ret_ty = sig.output();
+        let hir_id = tcx.hir().as_local_hir_id(def_id).unwrap();
+
+        for meta_item in attr.meta_item_list().unwrap_or_default() {
+            match meta_item.name_or_empty() {
+                sym::instantiate_in_obligation => {
+                    let sized_trait = tcx.lang_items().sized_trait().unwrap();
+                    let trait_ref = ty::TraitRef {
+                        def_id: sized_trait,
+                        substs: tcx.mk_substs_trait(ret_ty, &[]),
+                    };
+                    tcx.infer_ctxt().enter(|infcx| {
+                        let obligation = Obligation::new(
+                            ObligationCause::misc(span, hir_id),
+                            param_env,
+                            trait_ref.to_predicate(),
+                        );
+                        let InferOk { value: (obligation, opaque_types), obligations } =
+                            infcx.instantiate_opaque_types_in_obligation(def_id, &obligation);
+                        let obligations: Vec<_> = obligations
+                            .iter()
+                            .map(|o| format!("`{}`", o.predicate))
+                            .collect();
+                        tcx.sess.span_err(
+                            span,
+                            &format!(
+                                "instantiate_in_obligation: `{}`, {} opaque type{}, \
+                                 obligations: [{}]",
+                                obligation.predicate,
+                                opaque_types.len(),
+                                if opaque_types.len() == 1 { "" } else { "s" },
+                                obligations.join(", "),
+                            ),
+                        );
+                    });
+                }
+
+                sym::region_bounds_cache => {
+                    tcx.infer_ctxt().enter(|infcx| {
+                        // Instantiating an opaque type looks up its required
+                        // region bounds, so this reports whether they were
+                        // already in the cache. The instantiations to look for
+                        // are found in a snapshot, whose rollback empties it.
+                        let instantiations: Vec<_> = infcx.probe(|_| {
+                            let InferOk { value: (_, opaque_types), .. } =
+                                infcx.instantiate_opaque_types(def_id, hir_id, param_env, &ret_ty);
+                            opaque_types
+                                .into_iter()
+                                .map(|(opaque_def_id, opaque_defn)| {
+                                    (opaque_def_id, opaque_defn.substs)
+                                })
+                                .collect()
+                        });
+                        let instantiate = || {
+                            let cached = instantiations.iter().all(|&(opaque_def_id, substs)| {
+                                infcx.opaque_region_bounds_cached(opaque_def_id, substs)
+                            });
+                            infcx.instantiate_opaque_types(def_id, hir_id, param_env, &ret_ty);
+                            if cached { "hit" } else { "miss" }
+                        };
+                        let first = instantiate();
+                        let second = instantiate();
+                        let in_snapshot = infcx.probe(|_| instantiate());
+                        let after_rollback = instantiate();
+                        tcx.sess.span_err(
+                            span,
+                            &format!(
+                                "region_bounds_cache: {}, {}, {} in snapshot, {} after rollback",
+                                first, second, in_snapshot, after_rollback,
+                            ),
+                        );
+                    });
+                }
+
+                sym::region_acceptable => {
+                    let mut regions = vec![];
+                    for &input in sig.inputs() {
+                        tcx.for_each_free_region(&input, |r| if !regions.contains(&r) {
+                            regions.push(r);
+                        });
+                    }
+                    tcx.infer_ctxt().enter(|infcx| {
+                        // Only the relations declared in the where-clauses are
+                        // known, not the ones implied by the argument types.
+                        let outlives_env = OutlivesEnvironment::new(param_env);
+                        let InferOk { value: (_, opaque_types), .. } =
+                            infcx.instantiate_opaque_types(def_id, hir_id, param_env, &ret_ty);
+                        let mut opaque_types: Vec<_> = opaque_types.into_iter().collect();
+                        opaque_types.sort_by_key(|&(opaque_def_id, _)| opaque_def_id);
+                        for (opaque_def_id, opaque_defn) in &opaque_types {
+                            let acceptable: Vec<_> = regions
+                                .iter()
+                                .map(|&r| {
+                                    let acceptable = infcx.region_acceptable_for_opaque(
+                                        *opaque_def_id,
+                                        opaque_defn,
+                                        r,
+                                        outlives_env.free_region_map(),
+                                    );
+                                    format!("{}: {}", r, acceptable)
+                                })
+                                .collect();
+                            tcx.sess.span_err(
+                                span,
+                                &format!("region_acceptable: {}", acceptable.join(", ")),
+                            );
+                        }
+                    });
+                }
+
+                name => {
+                    tcx.sess.span_err(
+                        meta_item.span(),
+                        &format!("unrecognized field name `{}`", name),
+                    );
+                }
+            }
+        }
+    }
+}
@@ -1126,12 +1126,26 @@ pub const BUILTIN_ATTRIBUTES: &[BuiltinAttribute] = &[
                                     is just used for rustc unit tests \
                                     and will never be stable",
                                     cfg_fn!(rustc_attrs))),
+    (sym::rustc_opaque_types, Normal, template!(List: "op1, op2, ..."),
+    Gated(Stability::Unstable,
+        sym::rustc_attrs,
+        "the `#[rustc_opaque_types]` attribute \
+            is just used for rustc unit tests \
+            and will never be stable",
+        cfg_fn!(rustc_attrs))),
     (sym::rustc_variance, Normal, template!(Word), Gated(Stability::Unstable,
                                     sym::rustc_attrs,
                                     "the `#[rustc_variance]` attribute \
                                     is just used for rustc unit tests \
                                     and will never be stable",
                                     cfg_fn!(rustc_attrs))),
+    (sym::rustc_lattice, Normal, template!(List: "op1, op2, ..."),
+    Gated(Stability::Unstable,
+        sym::rustc_attrs,
+        "the `#[rustc_lattice]` attribute \
+            is just used for rustc unit tests \
+            and will never be stable",
+        cfg_fn!(rustc_attrs))),
     (sym::rustc_layout, Normal, template!(List: "field1, field2, ..."),
     Gated(Stability::Unstable,
         sym::rustc_attrs,
@@ -159,6 +159,7 @@ symbols! {
         block,
         borrowck_graphviz_postflow,
         borrowck_graphviz_preflow,
+        borrowck_live_start,
         box_patterns,
         box_syntax,
         braced_empty_structs,
@@ -295,6 +296,8 @@ symbols! {
         generators,
         generic_associated_types,
         generic_param_attrs,
+        glb_lub,
+        glb_n,
         global_allocator,
         global_asm,
         globs,
@@ -331,6 +334,7 @@ symbols! {
         infer_outlives_requirements,
         infer_static_outlives_requirements,
         inline,
+        instantiate_in_obligation,
         intel,
         into_iter,
         IntoIterator,
@@ -499,6 +503,8 @@ symbols! {
         recursion_limit,
         reexport_test_harness_main,
         reflect,
+        region_acceptable,
+        region_bounds_cache,
         relaxed_adts,
         repr,
         repr128,
@@ -541,6 +547,7 @@ symbols! {
         rustc_expected_cgu_reuse,
         rustc_if_this_changed,
         rustc_inherit_overflow_checks,
+        rustc_lattice,
         rustc_layout,
         rustc_layout_scalar_valid_range_end,
         rustc_layout_scalar_valid_range_start,
@@ -548,12 +555,14 @@ symbols! {
         rustc_nonnull_optimization_guaranteed,
         rustc_object_lifetime_default,
         rustc_on_unimplemented,
+        rustc_opaque_types,
         rustc_outlives,
         rustc_paren_sugar,
         rustc_partition_codegened,
         rustc_partition_reused,
         rustc_peek,
         rustc_peek_definite_init,
+        rustc_peek_helper,
         rustc_peek_maybe_init,
         rustc_peek_maybe_uninit,
         rustc_private,
@@ -603,6 +612,7 @@ symbols! {
         stmt,
         stmt_expr_attributes,
         stop_after_dataflow,
+        stop_at_call,
         struct_field_attributes,
         struct_inherit,
         structural_match,
@@ -635,6 +645,7 @@ symbols! {
         trivial_bounds,
         Try,
         try_blocks,
+        try_glb,
         try_trait,
         tt,
         tuple_indexing,
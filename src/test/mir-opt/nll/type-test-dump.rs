@@ -0,0 +1,23 @@
+// Check that the type tests registered during MIR type-check show up in
+// the NLL dump. Passing `&x` to `outlives` requires `T: 'a` for the
+// inferred `'a`, which can only be met by the body of `foo`.
+
+// compile-flags:-Zborrowck=mir -Zverbose
+//                              ^^^^^^^^^ force compiler to dump more region information
+
+#![allow(warnings)]
+
+fn outlives<'a, T: 'a>(_: &'a T) {}
+
+fn foo<T>(x: T) {
+    outlives(&x);
+}
+
+fn main() {
+}
+
+// END RUST SOURCE
+// START rustc.foo.nll.0.mir
+// | Type Tests
+// | T: '_#4r (at bb0[3]) verify { any('_#1r) }
+// END rustc.foo.nll.0.mir
@@ -23,8 +23,8 @@ impl Drop for Droppy {
 //     let mut _3: Droppy;
 //     let mut _4: Aligned;
 //     let mut _5: Droppy;
-//     let mut _6: Aligned;
 //     scope 1 {
+//         let mut _6: Aligned;
 //     }
 //
 //     bb0: {
@@ -0,0 +1,39 @@
+// ignore-wasm32-bare compiled with panic=abort by default
+
+// Check that the temporary a field of a packed struct is moved into before
+// being dropped is declared in the scope of the drop, so that it shows up in
+// debuginfo (as `p.data`).
+
+fn main() {
+    let mut p = Packed { dealign: 0, data: Droppy(0) };
+    p.data = Droppy(1);
+}
+
+#[repr(packed)]
+struct Packed {
+    dealign: u8,
+    data: Droppy,
+}
+
+struct Droppy(usize);
+impl Drop for Droppy {
+    fn drop(&mut self) {}
+}
+
+// END RUST SOURCE
+// START rustc.main.AddMovesForPackedDrops.after.mir
+// fn main() -> () {
+//     ...
+//     scope 1 {
+//         let mut _4: Droppy;
+//     }
+//     ...
+//     bb0: {
+//         ...
+//         StorageLive(_4);
+//         _4 = move (_1.1: Droppy);
+//         drop(_4) -> [return: bb4, unwind: bb3];
+//     }
+//     ...
+// }
+// END rustc.main.AddMovesForPackedDrops.after.mir
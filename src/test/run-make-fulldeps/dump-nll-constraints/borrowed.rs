@@ -0,0 +1,5 @@
+// Returning `x` requires the region of its type to outlive the region of the
+// return type, which the type-checker records as a `Return` constraint.
+pub fn identity<'a>(x: &'a u32) -> &'a u32 {
+    x
+}
@@ -0,0 +1,12 @@
+// Passing `&x` to `outlives` requires `T::Item: 'a` for the inferred `'a`. It
+// holds if `'a` is outlived by the bound declared in the where clause, or by
+// the bounds of all of the components of the projection, so the verify bound
+// of the type test nests one list of bounds within another. The regions it
+// mentions are listed without going through its rendered form, which only
+// summarizes nested bounds.
+
+fn outlives<'a, T: 'a>(_: &'a T) {}
+
+pub fn foo<'b, T: Iterator>(x: T::Item) where T::Item: 'b {
+    outlives(&x);
+}
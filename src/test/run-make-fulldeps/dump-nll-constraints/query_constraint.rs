@@ -0,0 +1,12 @@
+// Proving the where-clause of `shorten` at the call site is done by a query,
+// whose region constraint `'a: 'b` is converted into an outlives constraint.
+// The dump prints that query constraint as the source of the outlives
+// constraint.
+
+fn shorten<'a, 'b>(x: &'a u32) -> &'b u32 where 'a: 'b {
+    x
+}
+
+pub fn caller<'a, 'b>(x: &'a u32) -> &'b u32 where 'a: 'b {
+    shorten(x)
+}
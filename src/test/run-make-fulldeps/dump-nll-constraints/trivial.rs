@@ -0,0 +1,13 @@
+// Relating the regions of these references, and proving that the item type,
+// which is bounded by `'static`, outlives the region of the trait object,
+// would give constraints `'a: 'a` and `'static: 'a`, which always hold. The
+// dump must not print any such constraint.
+
+pub trait Source {
+    type Item: Fn() -> usize + 'static;
+}
+
+pub fn keep<'a, S: Source>(item: S::Item, x: &'a u32) -> (Box<dyn Fn() -> usize + 'a>, &'a u32) {
+    let y: &'a u32 = x;
+    (Box::new(item), y)
+}
@@ -0,0 +1,10 @@
+// Each of the calls to `outlives` registers a type test, one for `T` and one
+// for `U`. The dump lists the type tests of each type separately, so each of
+// them shows up exactly once.
+
+fn outlives<'a, T: 'a>(_: &'a T) {}
+
+pub fn foo<T, U>(x: T, y: U) {
+    outlives(&x);
+    outlives(&y);
+}
@@ -0,0 +1,9 @@
+// Passing `&x` to `outlives` requires `T: 'a` for the inferred `'a`, which the
+// type-checker records as a type test. The dump prints it with the `Display`
+// impl of `TypeTest`.
+
+fn outlives<'a, T: 'a>(_: &'a T) {}
+
+pub fn foo<T>(x: T) {
+    outlives(&x);
+}
@@ -0,0 +1,47 @@
+// Each function has 100 live borrows, of the fields of 10 different roots, and
+// then checks a single access against all of them with `any_conflict`, which
+// only compares the access with the borrows of its own root, and with the
+// naive loop over all of the borrows.
+
+#![feature(core_intrinsics, rustc_attrs)]
+
+use std::intrinsics::rustc_peek;
+
+pub struct Fields {
+    pub f0: u32,
+    pub f1: u32,
+    pub f2: u32,
+    pub f3: u32,
+    pub f4: u32,
+    pub f5: u32,
+    pub f6: u32,
+    pub f7: u32,
+    pub f8: u32,
+    pub f9: u32,
+}
+
+macro_rules! live_borrows_then_access {
+    ($name:ident($($root:ident),*; $other:ident) => $access:expr) => {
+        #[rustc_mir(rustc_peek_helper="any_conflict")]
+        pub fn $name($($root: Fields,)* $other: Fields) {
+            $(
+                rustc_peek(&$root.f0);
+                rustc_peek(&$root.f1);
+                rustc_peek(&$root.f2);
+                rustc_peek(&$root.f3);
+                rustc_peek(&$root.f4);
+                rustc_peek(&$root.f5);
+                rustc_peek(&$root.f6);
+                rustc_peek(&$root.f7);
+                rustc_peek(&$root.f8);
+                rustc_peek(&$root.f9);
+            )*
+            rustc_peek(&$access);
+        }
+    };
+}
+
+live_borrows_then_access!(unrelated_root(s0, s1, s2, s3, s4, s5, s6, s7, s8, s9; other) =>
+    other.f0);
+live_borrows_then_access!(last_root(s0, s1, s2, s3, s4, s5, s6, s7, s8, s9; _other) =>
+    s9.f9);
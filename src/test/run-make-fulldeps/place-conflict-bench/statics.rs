@@ -0,0 +1,41 @@
+// Each function borrows fields of a static, and then reads fields of it while
+// all of the borrows are live, so each read is checked against each borrow.
+// When a read and a borrow are of the same field, the places are equal, which
+// is decided without walking their projections in lockstep.
+
+pub struct Inner {
+    pub a: u32,
+    pub b: u32,
+    pub c: u32,
+    pub d: u32,
+}
+
+pub struct Fields {
+    pub f0: Inner,
+    pub f1: Inner,
+    pub f2: Inner,
+    pub f3: Inner,
+}
+
+const INNER: Inner = Inner { a: 0, b: 1, c: 2, d: 3 };
+
+pub static FIELDS: Fields = Fields { f0: INNER, f1: INNER, f2: INNER, f3: INNER };
+
+macro_rules! borrow_then_read {
+    ($name:ident: $($outer:ident.$inner:ident),*) => {
+        pub fn $name() -> u32 {
+            let borrows = [$(&FIELDS.$outer.$inner),*];
+            let reads = [$(FIELDS.$outer.$inner),*];
+            borrows.iter().map(|&&b| b).sum::<u32>() + reads.iter().sum::<u32>()
+        }
+    };
+}
+
+borrow_then_read!(all_fields:
+    f0.a, f0.b, f0.c, f0.d, f1.a, f1.b, f1.c, f1.d, f2.a, f2.b, f2.c, f2.d, f3.a, f3.b, f3.c,
+    f3.d
+);
+borrow_then_read!(same_field:
+    f0.a, f0.a, f0.a, f0.a, f0.a, f0.a, f0.a, f0.a, f0.a, f0.a, f0.a, f0.a, f0.a, f0.a, f0.a,
+    f0.a
+);
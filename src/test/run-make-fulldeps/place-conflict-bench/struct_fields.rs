@@ -0,0 +1,44 @@
+// Each function borrows every field of a struct, and then reads every field
+// while all of the borrows are live, so each read is checked against each
+// borrow. The fields are different, so the walk goes down to the fields of
+// the (non-union) struct every time.
+
+pub struct Inner {
+    pub a: u32,
+    pub b: u32,
+    pub c: u32,
+    pub d: u32,
+}
+
+pub struct Fields {
+    pub f0: Inner,
+    pub f1: Inner,
+    pub f2: Inner,
+    pub f3: Inner,
+    pub f4: Inner,
+    pub f5: Inner,
+    pub f6: Inner,
+    pub f7: Inner,
+}
+
+macro_rules! borrow_then_read {
+    ($name:ident($s:ident): $($outer:ident.$inner:ident),*) => {
+        pub fn $name($s: Fields) -> u32 {
+            let borrows = [$(&$s.$outer.$inner),*];
+            let reads = [$($s.$outer.$inner),*];
+            borrows.iter().map(|&&b| b).sum::<u32>() + reads.iter().sum::<u32>()
+        }
+    };
+}
+
+borrow_then_read!(all_fields(s):
+    f0.a, f0.b, f0.c, f0.d, f1.a, f1.b, f1.c, f1.d, f2.a, f2.b, f2.c, f2.d, f3.a, f3.b, f3.c,
+    f3.d, f4.a, f4.b, f4.c, f4.d, f5.a, f5.b, f5.c, f5.d, f6.a, f6.b, f6.c, f6.d, f7.a, f7.b,
+    f7.c, f7.d
+);
+borrow_then_read!(outer_fields(s):
+    f0.a, f1.a, f2.a, f3.a, f4.a, f5.a, f6.a, f7.a
+);
+borrow_then_read!(inner_fields(s):
+    f0.a, f0.b, f0.c, f0.d
+);
@@ -0,0 +1,13 @@
+pub struct Pair {
+    a: u32,
+    b: u32,
+}
+
+// Accessing `p.b` while `p.a` is borrowed compares `*p` with `*p`, and then
+// `(*p).a` with `(*p).b`.
+pub fn disjoint_fields(p: &mut Pair) -> u32 {
+    let a = &mut p.a;
+    p.b += 1;
+    *a += 1;
+    p.b
+}
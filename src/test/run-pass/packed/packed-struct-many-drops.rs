@@ -0,0 +1,67 @@
+// run-pass
+// Like packed-struct-drop-aligned.rs, but with dozens of drops of packed
+// fields in one function, which each get moved to an aligned temporary first.
+use std::cell::Cell;
+use std::mem;
+
+struct Aligned<'a> {
+    drop_count: &'a Cell<usize>
+}
+
+#[inline(never)]
+fn check_align(ptr: *const Aligned) {
+    assert_eq!(ptr as usize % mem::align_of::<Aligned>(),
+               0);
+}
+
+impl<'a> Drop for Aligned<'a> {
+    fn drop(&mut self) {
+        check_align(self);
+        self.drop_count.set(self.drop_count.get() + 1);
+    }
+}
+
+#[repr(packed)]
+struct Packed<'a>(
+    u8,
+    Aligned<'a>, Aligned<'a>, Aligned<'a>, Aligned<'a>,
+    Aligned<'a>, Aligned<'a>, Aligned<'a>, Aligned<'a>,
+    Aligned<'a>, Aligned<'a>, Aligned<'a>, Aligned<'a>,
+    Aligned<'a>, Aligned<'a>, Aligned<'a>, Aligned<'a>,
+    Aligned<'a>, Aligned<'a>, Aligned<'a>, Aligned<'a>,
+    Aligned<'a>, Aligned<'a>, Aligned<'a>, Aligned<'a>,
+    Aligned<'a>, Aligned<'a>, Aligned<'a>, Aligned<'a>,
+    Aligned<'a>, Aligned<'a>, Aligned<'a>, Aligned<'a>,
+);
+
+macro_rules! reassign {
+    ($p:ident, $drop_count:ident; $($field:tt)*) => {
+        $($p.$field = Aligned { drop_count: $drop_count };)*
+    }
+}
+
+fn main() {
+    let drop_count = &Cell::new(0);
+    {
+        let a = || Aligned { drop_count };
+        let mut p = Packed(
+            0,
+            a(), a(), a(), a(),
+            a(), a(), a(), a(),
+            a(), a(), a(), a(),
+            a(), a(), a(), a(),
+            a(), a(), a(), a(),
+            a(), a(), a(), a(),
+            a(), a(), a(), a(),
+            a(), a(), a(), a(),
+        );
+        reassign!(p, drop_count;
+            1 2 3 4 5 6 7 8
+            9 10 11 12 13 14 15 16
+            17 18 19 20 21 22 23 24
+            25 26 27 28 29 30 31 32
+        );
+        assert_eq!(drop_count.get(), 32);
+    }
+    assert_eq!(drop_count.get(), 64);
+}
@@ -0,0 +1,17 @@
+// edition:2018
+
+#![feature(async_await)]
+
+// Check that ambiguous lifetimes in an `async fn` come with a suggestion to
+// use a single named lifetime for all of the elided ones.
+
+async fn elided(_: &u8, _: &mut u8) {}
+//~^ ERROR ambiguous lifetime bound in `async fn`
+
+async fn elided_and_explicit<'b>(_: &u8, _: &'b u8, _: &'_ u8) {}
+//~^ ERROR ambiguous lifetime bound in `async fn`
+
+async fn nested(_: &Vec<&u8>) {}
+//~^ ERROR ambiguous lifetime bound in `async fn`
+
+fn main() {}
@@ -0,0 +1,27 @@
+// Checks a borrow of a field of an array element against an access to a
+// subslice of the array. The two are compared with the element and the
+// subslice having different types, which used to ICE.
+
+// compile-flags: -Z borrowck=mir
+
+#![feature(slice_patterns)]
+
+struct S { x: u32 }
+
+fn nop(_s: &[S]) {}
+
+fn field_of_element_and_subslice_ok(mut a: [S; 4]) {
+    let [_, S { ref mut x }, ..] = a;
+    let [_, _, ref tail..] = a;
+    *x = 1;
+    nop(tail);
+}
+
+fn field_of_element_and_subslice_err(mut a: [S; 4]) {
+    let [_, _, _, S { ref mut x }] = a;
+    let [_, _, ref tail..] = a; //~ ERROR cannot borrow `a[..]` as immutable
+    *x = 1;
+    nop(tail);
+}
+
+fn main() {}
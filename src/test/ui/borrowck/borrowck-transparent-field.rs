@@ -0,0 +1,20 @@
+// Conflicting borrows of the single field of a `#[repr(transparent)]` wrapper
+// are reported in terms of the wrapper itself.
+
+#[repr(transparent)]
+struct Wrapper<T>(T);
+
+fn main() {
+    let mut x = Wrapper(0u32);
+    let a = &x;
+    let b = &mut x.0; //~ ERROR cannot borrow `x` as mutable because it is also borrowed as
+    a.use_ref();
+
+    let mut y = Wrapper(0u32);
+    let c = &mut y.0;
+    let d = &mut y.0; //~ ERROR cannot borrow `y` as mutable more than once at a time
+    c.use_mut();
+}
+
+trait Fake { fn use_mut(&mut self) { } fn use_ref(&self) { }  }
+impl<T> Fake for T { }
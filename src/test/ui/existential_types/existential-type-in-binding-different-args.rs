@@ -0,0 +1,18 @@
+// Checks that an existential type used in the type of a local of its
+// defining function must have the same generic arguments as in the
+// return type.
+
+#![feature(existential_type, impl_trait_in_bindings)]
+//~^ WARN the feature `impl_trait_in_bindings` is incomplete
+
+use std::fmt::Debug;
+
+existential type Foo<T>: Debug;
+
+fn define<T: Debug>(t: T) -> Foo<T> {
+    let x: Foo<u8> = 1u8;
+    //~^ ERROR existential type `Foo` is used with different generic arguments in the same body
+    t
+}
+
+fn main() {}
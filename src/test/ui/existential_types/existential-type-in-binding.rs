@@ -0,0 +1,20 @@
+// compile-pass
+
+// Checks that an existential type can be used both in the return type and
+// in the type of a local of its defining function.
+
+#![feature(existential_type, impl_trait_in_bindings)]
+//~^ WARN the feature `impl_trait_in_bindings` is incomplete
+
+use std::fmt::Debug;
+
+existential type Foo: Debug;
+
+fn define() -> Foo {
+    let x: Foo = 22u32;
+    x
+}
+
+fn main() {
+    println!("{:?}", define());
+}
@@ -0,0 +1,22 @@
+// compile-flags: -Z dump-opaque-types
+// edition:2018
+// compile-pass
+
+// Checks that the opaque return type of an `async fn` is reported as
+// such, and the return type of a plain `-> impl Future` function is not.
+
+#![feature(async_await)]
+
+use std::future::Future;
+
+async fn async_fn() -> u32 {
+    22
+}
+
+fn impl_future() -> impl Future<Output = u32> {
+    async_fn()
+}
+
+fn main() {
+    let _ = impl_future();
+}
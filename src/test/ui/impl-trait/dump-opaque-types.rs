@@ -0,0 +1,29 @@
+// compile-flags: -Z dump-opaque-types
+// compile-pass
+
+use std::fmt::{Debug, Display};
+
+fn concrete() -> impl Debug {
+    22u32
+}
+
+fn generic<T: Debug>(t: T) -> impl Debug {
+    t
+}
+
+fn pair() -> (impl Debug, impl Display) {
+    (22u32, String::new())
+}
+
+// The closure captures a `'static` reference but not the lifetime of the
+// enclosing function.
+fn closure<'a, T: 'a>(_t: &'a T, x: &'static u32) -> impl Fn() -> u32 {
+    move || *x
+}
+
+fn main() {
+    concrete();
+    generic(());
+    pair();
+    closure(&(), &22);
+}
@@ -0,0 +1,11 @@
+// Check that we do not suggest `+ 'static` when the hidden type also
+// borrows a region from the bounds.
+
+use std::marker::PhantomData;
+
+fn borrows<'a, 'b: 'a>(x: &'a u32, _y: &'b u32) -> impl Sized + 'a {
+    //~^ ERROR hidden type for `impl Trait` captures lifetime that does not appear in bounds
+    (x, PhantomData::<&'b ()>)
+}
+
+fn main() {}
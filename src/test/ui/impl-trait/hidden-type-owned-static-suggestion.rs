@@ -0,0 +1,11 @@
+// Check that we suggest `+ 'static` when the only region the hidden type
+// captures is the one missing from the bounds.
+
+use std::marker::PhantomData;
+
+fn owned<'a, 'b: 'a>(_x: &'b u32) -> impl Sized + 'a {
+    //~^ ERROR hidden type for `impl Trait` captures lifetime that does not appear in bounds
+    PhantomData::<&'b ()>
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+// Check that region errors for an `impl Trait` without lifetime parameters
+// explain why its hidden type is required to be `'static`.
+
+struct Bytes {
+    data: [u8; 4],
+}
+
+fn bytes(b: &Bytes) -> impl Iterator<Item = u8> {
+    b.data.iter().cloned() //~ ERROR cannot infer an appropriate lifetime
+}
+
+fn main() {}
@@ -0,0 +1,17 @@
+// Checks that `instantiate_opaque_types_in_obligation` replaces the
+// opaque types in the predicate of an obligation (here, `ret: Sized` for
+// the return type `ret`) and returns the obligations for their bounds.
+
+#![feature(rustc_attrs)]
+
+use std::fmt::Debug;
+
+#[rustc_opaque_types(instantiate_in_obligation)]
+fn opaque() -> impl Debug { 22u32 }
+//~^ ERROR instantiate_in_obligation: `_: std::marker::Sized`, 1 opaque type
+
+#[rustc_opaque_types(instantiate_in_obligation)]
+fn plain() -> u32 { 22 }
+//~^ ERROR instantiate_in_obligation: `u32: std::marker::Sized`, 0 opaque types
+
+fn main() {}
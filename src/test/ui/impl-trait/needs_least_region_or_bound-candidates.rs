@@ -0,0 +1,14 @@
+// Check that an ambiguous lifetime bound between more than two lifetimes
+// lists all of the candidate lifetimes.
+
+trait MultiRegionTrait<'a, 'b, 'c> {}
+impl<'a, 'b, 'c> MultiRegionTrait<'a, 'b, 'c> for (&'a u32, &'b u32, &'c u32) {}
+
+fn no_least_region<'a, 'b, 'c>(x: &'a u32, y: &'b u32, z: &'c u32)
+    -> impl MultiRegionTrait<'a, 'b, 'c>
+    //~^ ERROR ambiguous lifetime bound
+{
+    (x, y, z)
+}
+
+fn main() {}
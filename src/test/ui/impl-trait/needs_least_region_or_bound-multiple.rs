@@ -0,0 +1,15 @@
+// Check that errors for several opaque types in the same signature are
+// reported in a deterministic order.
+
+trait MultiRegionTrait<'a, 'b> {}
+impl<'a, 'b> MultiRegionTrait<'a, 'b> for (&'a u32, &'b u32) {}
+
+fn no_least_region<'a, 'b>(x: &'a u32, y: &'b u32)
+    -> (impl MultiRegionTrait<'a, 'b>, impl MultiRegionTrait<'a, 'b>)
+    //~^ ERROR ambiguous lifetime bound
+    //~| ERROR ambiguous lifetime bound
+{
+    ((x, y), (x, y))
+}
+
+fn main() {}
@@ -0,0 +1,8 @@
+// compile-flags: -Z opaque-max-nesting=2
+
+fn nested() -> impl Iterator<Item = impl Iterator<Item = impl Copy>> {
+//~^ ERROR reached the nesting limit while instantiating `impl Trait` types
+    vec![vec![0u8]].into_iter().map(|v| v.into_iter())
+}
+
+fn main() {}
@@ -0,0 +1,14 @@
+// Checks that the required region bounds of an opaque type are computed
+// once per inference context, and computed again after a snapshot that
+// may have cached bounds referring to its inference variables is rolled
+// back.
+
+#![feature(rustc_attrs)]
+
+use std::fmt::Debug;
+
+#[rustc_opaque_types(region_bounds_cache)]
+fn bounded<'a>(x: &'a u32) -> impl Debug + 'a { x }
+//~^ ERROR region_bounds_cache: miss, hit, hit in snapshot, miss after rollback
+
+fn main() {}
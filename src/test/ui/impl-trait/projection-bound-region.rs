@@ -0,0 +1,34 @@
+// Test that regions appearing only in an associated-type equality of an
+// `impl Trait` are taken into account when constraining the hidden type,
+// and that regions bound in such an equality (as in `impl Fn(&u8) -> &u8`)
+// are not mistaken for candidates.
+// run-pass
+
+fn bytes<'a>(x: &'a [u8]) -> impl Iterator<Item = &'a u8> {
+    x.iter()
+}
+
+fn bytes_with_ignored<'a, 'b>(x: &'a [u8], _: &'b u32) -> impl Iterator<Item = &'a u8> + 'a {
+    x.iter().filter(|&&b| b != 0)
+}
+
+trait Captures<'a> {}
+
+impl<'a, T: ?Sized> Captures<'a> for T {}
+
+// The lifetimes elided in the `Fn` bound are bound by it; only `'a` may be
+// captured.
+fn higher_ranked<'a>(x: &'a u8) -> impl Fn(&u8) -> &u8 + Captures<'a> {
+    move |y| {
+        let _ = x;
+        y
+    }
+}
+
+fn main() {
+    let v = vec![0, 1, 2];
+    let u = 3;
+    assert_eq!(bytes(&v).count(), 3);
+    assert_eq!(bytes_with_ignored(&v, &u).count(), 2);
+    assert_eq!(*higher_ranked(&u)(&v[1]), 1);
+}
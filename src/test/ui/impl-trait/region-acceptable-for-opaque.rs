@@ -0,0 +1,31 @@
+// Checks which regions `region_acceptable_for_opaque` accepts in the hidden
+// type of an opaque type: with a region bound, regions outliving the bound;
+// without one, regions outliving one of the lifetime parameters of the
+// opaque type.
+
+#![feature(rustc_attrs)]
+
+use std::fmt::Debug;
+
+trait Captures<'a> {}
+impl<T> Captures<'_> for T {}
+
+#[rustc_opaque_types(region_acceptable)]
+fn bounded<'a, 'b, 'c>(x: &'a u32, _y: &'b u32, _z: &'c u32) -> impl Debug + 'a where 'b: 'a {
+//~^ ERROR region_acceptable: 'a: true, 'b: true, 'c: false
+    x
+}
+
+#[rustc_opaque_types(region_acceptable)]
+fn unbounded<'a>(_x: &'a u32) -> impl Debug {
+//~^ ERROR region_acceptable: 'a: false
+    22u32
+}
+
+#[rustc_opaque_types(region_acceptable)]
+fn captures<'a, 'b>(_x: &'a u32, _y: &'b u32) -> impl Debug + Captures<'a> {
+//~^ ERROR region_acceptable: 'a: true, 'b: false
+    22u32
+}
+
+fn main() {}
@@ -0,0 +1,20 @@
+// compile-pass
+
+// Check that an `impl Trait` with a required region bound whose hidden type
+// contains no regions at all is accepted.
+
+use std::fmt::Debug;
+
+fn region_free<'a>(_: &'a u32) -> impl Debug + 'a {
+    22u32
+}
+
+fn region_free_nested<'a, 'b: 'a>(_: &'a u32, _: &'b u32) -> impl Iterator<Item = u8> + 'a {
+    vec![1, 2, 3].into_iter()
+}
+
+fn main() {
+    let x = 0;
+    println!("{:?}", region_free(&x));
+    assert_eq!(region_free_nested(&x, &x).count(), 3);
+}
@@ -0,0 +1,23 @@
+// Checks that `glb_lub` computes both bounds in one snapshot: when the
+// second half of a pair fails to relate, the region constraints from the
+// first half are rolled back too.
+
+#![feature(rustc_attrs)]
+
+#[rustc_lattice(glb_lub)]
+fn refs<'a, 'b>(a: &'a u32, b: &'b u32) {}
+//~^ ERROR glb_lub: (&u32, &u32) (4 region constraints)
+
+#[rustc_lattice(glb_lub)]
+fn same<'a>(a: &'a u32, b: &'a u32) {}
+//~^ ERROR glb_lub: (&'a u32, &'a u32) (0 region constraints)
+
+#[rustc_lattice(glb_lub)]
+fn partial<'a, 'b>(a: (&'a u32, u8), b: (&'b u32, i8)) {}
+//~^ ERROR glb_lub: expected `u8`, found `i8` (0 region constraints)
+
+#[rustc_lattice(glb_lub)]
+fn three(a: u32, b: u32, c: u32) {}
+//~^ ERROR glb_lub: expected two arguments
+
+fn main() {}
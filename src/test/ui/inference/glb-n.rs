@@ -0,0 +1,30 @@
+// Checks that `glb_n` relates every distinct type to a single fresh
+// bound, creating one region constraint per type, instead of creating
+// a fresh bound for every pair like folding `glb` does.
+
+#![feature(rustc_attrs)]
+
+#[rustc_lattice(glb_n)]
+fn four<'a, 'b, 'c, 'd>(a: &'a u32, b: &'b u32, c: &'c u32, d: &'d u32) {}
+//~^ ERROR glb_n: &u32 (4 region constraints)
+//~| ERROR pairwise glb: &u32 (6 region constraints)
+
+#[rustc_lattice(glb_n)]
+fn repeated<'a, 'b>(a: &'a u32, b: &'b u32, c: &'a u32) {}
+//~^ ERROR glb_n: &u32 (2 region constraints)
+//~| ERROR pairwise glb: &u32 (4 region constraints)
+
+#[rustc_lattice(glb_n)]
+fn one<'a>(a: &'a u32) {}
+//~^ ERROR glb_n: &'a u32 (0 region constraints)
+//~| ERROR pairwise glb: &'a u32 (0 region constraints)
+
+#[rustc_lattice(glb_n)]
+fn mismatch(a: u32, b: i32) {}
+//~^ ERROR glb_n: expected `u32`, found `i32`
+
+#[rustc_lattice(glb_n)]
+fn empty() {}
+//~^ ERROR glb_n: types differ
+
+fn main() {}
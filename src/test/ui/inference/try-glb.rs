@@ -0,0 +1,18 @@
+// Checks that `try_glb` returns `None` when the types have no
+// greatest-lower-bound and leaves no region constraints behind.
+
+#![feature(rustc_attrs)]
+
+#[rustc_lattice(try_glb)]
+fn refs<'a, 'b>(a: &'a u32, b: &'b u32) {}
+//~^ ERROR try_glb: &u32 (2 region constraints)
+
+#[rustc_lattice(try_glb)]
+fn partial<'a, 'b>(a: (&'a u32, u8), b: (&'b u32, i8)) {}
+//~^ ERROR try_glb: none (0 region constraints)
+
+#[rustc_lattice(try_glb)]
+fn one(a: u32) {}
+//~^ ERROR try_glb: expected two arguments
+
+fn main() {}
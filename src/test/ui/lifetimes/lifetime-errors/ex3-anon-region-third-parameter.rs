@@ -0,0 +1,12 @@
+// Check that lifetime errors name a parameter by its position when its
+// pattern is not a simple binding.
+
+fn named<'a>(x: &'a i32, _: u8, (y, _): (&i32, u8)) -> &'a i32 {
+    if x > y { x } else { y } //~ ERROR explicit lifetime required
+}
+
+fn anon(x: &mut Vec<&u8>, _: u8, (y, _): (&u8, u8)) {
+    x.push(y); //~ ERROR lifetime mismatch
+}
+
+fn main() {}
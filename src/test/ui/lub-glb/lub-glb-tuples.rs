@@ -0,0 +1,73 @@
+// run-pass
+// Check that the LUB of two tuples relates their elements pairwise, and so
+// takes the GLB of tuples in argument position, rather than requiring the
+// tuples to be equal. Each function below would fail to type-check if the
+// lifetimes of corresponding elements had to be equal.
+
+#![allow(dead_code)]
+
+fn lub_pair<'a, 'b>(n: usize, x: &'a u32, y: &'b u32) -> (&'a u32, &'b u32) {
+    let lub = match n {
+        0 => (x, &0),
+        _ => (&1, y),
+    };
+    lub
+}
+
+fn lub_triple<'a, 'b, 'c>(
+    n: usize,
+    x: &'a u32,
+    y: &'b u32,
+    z: &'c u32,
+) -> (&'a u32, &'b u32, &'c u32) {
+    let lub = match n {
+        0 => (x, &0, z),
+        _ => (&1, y, z),
+    };
+    lub
+}
+
+fn glb_pair<'a, 'b>(
+    n: usize,
+    f: fn((&'a u32, &'static u32)),
+    g: fn((&'static u32, &'b u32)),
+) -> fn((&'static u32, &'static u32)) {
+    let lub = match n {
+        0 => f,
+        _ => g,
+    };
+    lub
+}
+
+fn glb_triple<'a, 'b, 'c>(
+    n: usize,
+    f: fn((&'a u32, &'static u32, &'c u32)),
+    g: fn((&'static u32, &'b u32, &'static u32)),
+) -> fn((&'static u32, &'static u32, &'static u32)) {
+    let lub = match n {
+        0 => f,
+        _ => g,
+    };
+    lub
+}
+
+fn sum_pair((x, y): (&u32, &u32)) {
+    assert_eq!(x + y, 3);
+}
+
+fn sum_triple((x, y, z): (&u32, &u32, &u32)) {
+    assert_eq!(x + y + z, 6);
+}
+
+fn main() {
+    let n = std::env::args().count();
+    let (a, b, c) = (1, 2, 3);
+
+    let (x, y) = lub_pair(0, &a, &b);
+    assert_eq!((*x, *y), (1, 0));
+    let (x, y, z) = lub_triple(1, &a, &b, &c);
+    assert_eq!((*x, *y, *z), (1, 2, 3));
+
+    glb_pair(n, sum_pair, sum_pair)((&1, &2));
+    glb_triple(n, sum_triple, sum_triple)((&1, &2, &3));
+}
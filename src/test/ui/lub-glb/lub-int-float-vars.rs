@@ -0,0 +1,28 @@
+// run-pass
+// Check that the LUB of an integer (float) literal and a concrete integer
+// (float) type is that type, and that the LUB of two literals still falls
+// back to the default type.
+
+use std::mem::size_of_val;
+
+fn main() {
+    let n = std::env::args().count();
+
+    let int = match n {
+        0 => 0,
+        _ => 1i64,
+    };
+    assert_eq!(size_of_val(&int), 8);
+
+    let float = match n {
+        0 => 0.0,
+        _ => 1f32,
+    };
+    assert_eq!(size_of_val(&float), 4);
+
+    let literals = match n {
+        0 => 0,
+        _ => 1,
+    };
+    assert_eq!(size_of_val(&literals), 4);
+}
@@ -0,0 +1,63 @@
+// Test of `places_conflict::any_conflict`. In each function, all calls to
+// `rustc_peek` but the last are treated as mutable borrows of their arguments,
+// and the last call reports whether a deep access to its argument conflicts
+// with any of them.
+
+#![feature(core_intrinsics, rustc_attrs)]
+
+use std::intrinsics::rustc_peek;
+
+struct S(i32);
+struct Pair { a: S, b: S }
+
+static mut M: Pair = Pair { a: S(1), b: S(2) };
+
+#[rustc_mir(rustc_peek_helper="any_conflict")]
+fn no_borrows(x: Pair) {
+    rustc_peek(&x); //~ ERROR rustc_peek: no conflict
+}
+
+#[rustc_mir(rustc_peek_helper="any_conflict")]
+fn unrelated_root(x: Pair, y: Pair) {
+    rustc_peek(&x.a);
+    rustc_peek(&x.b);
+    rustc_peek(&y.a); //~ ERROR rustc_peek: no conflict
+}
+
+#[rustc_mir(rustc_peek_helper="any_conflict")]
+fn disjoint_fields(x: Pair) {
+    rustc_peek(&x.a);
+    rustc_peek(&x.b); //~ ERROR rustc_peek: no conflict
+}
+
+#[rustc_mir(rustc_peek_helper="any_conflict")]
+fn prefix_of_borrow(x: Pair, y: Pair) {
+    rustc_peek(&y.a);
+    rustc_peek(&x.a);
+    rustc_peek(&x); //~ ERROR rustc_peek: conflict
+}
+
+#[rustc_mir(rustc_peek_helper="any_conflict")]
+fn same_index_maybe(a: [S; 2], i: usize, j: usize) {
+    rustc_peek(&a[i]);
+    rustc_peek(&a[j]); //~ ERROR rustc_peek: conflict
+}
+
+#[rustc_mir(rustc_peek_helper="any_conflict")]
+fn static_mut() {
+    unsafe {
+        // Mutable statics are ignored, even when the places are equal.
+        rustc_peek(&M.a);
+        rustc_peek(&M.a); //~ ERROR rustc_peek: no conflict
+    }
+}
+
+fn main() {
+    let pair = || Pair { a: S(1), b: S(2) };
+    no_borrows(pair());
+    unrelated_root(pair(), pair());
+    disjoint_fields(pair());
+    prefix_of_borrow(pair(), pair());
+    same_index_maybe([S(3), S(4)], 0, 1);
+    static_mut();
+}
@@ -0,0 +1,46 @@
+// Test of `MoveData::common_ancestor`. Consecutive `rustc_peek` calls are
+// paired up, and the second call of each pair reports the deepest move path
+// that is a prefix of both arguments.
+
+#![feature(core_intrinsics, rustc_attrs)]
+
+use std::intrinsics::rustc_peek;
+use std::mem::drop;
+
+struct S(i32);
+struct Inner { a: S, b: S }
+struct Outer { inner: Inner, c: S }
+
+#[rustc_mir(rustc_peek_helper="common_ancestor",stop_after_dataflow)]
+fn foo(o: Outer, p: S) {
+    // Moving out of the fields makes all of them tracked.
+    drop(o.inner.a);
+    drop(o.inner.b);
+    drop(o.c);
+
+    // Siblings.
+    unsafe { rustc_peek(&o.inner.a); }
+    unsafe { rustc_peek(&o.inner.b); } //~ ERROR rustc_peek: common ancestor (_1.0: Inner)
+
+    // Fields at different depths.
+    unsafe { rustc_peek(&o.inner.a); }
+    unsafe { rustc_peek(&o.c); } //~ ERROR rustc_peek: common ancestor _1
+
+    // An ancestor and its descendant, in both orders.
+    unsafe { rustc_peek(&o); }
+    unsafe { rustc_peek(&o.inner.a); } //~ ERROR rustc_peek: common ancestor _1
+    unsafe { rustc_peek(&o.inner.b); }
+    unsafe { rustc_peek(&o.inner); } //~ ERROR rustc_peek: common ancestor (_1.0: Inner)
+
+    // A path and itself.
+    unsafe { rustc_peek(&o.c); }
+    unsafe { rustc_peek(&o.c); } //~ ERROR rustc_peek: common ancestor (_1.1: S)
+
+    // Unrelated roots.
+    unsafe { rustc_peek(&o); }
+    unsafe { rustc_peek(&p); } //~ ERROR rustc_peek: no common ancestor
+}
+
+fn main() {
+    foo(Outer { inner: Inner { a: S(1), b: S(2) }, c: S(3) }, S(4));
+}
@@ -0,0 +1,45 @@
+// Test of `places_conflict::places_definitely_disjoint`. Consecutive
+// `rustc_peek` calls are paired up, and the second call of each pair reports
+// whether the two arguments are proven to be disjoint.
+
+#![feature(core_intrinsics, rustc_attrs)]
+
+use std::intrinsics::rustc_peek;
+
+struct S(i32);
+struct Pair { a: S, b: S }
+union U { a: u32, b: u32 }
+
+#[rustc_mir(rustc_peek_helper="disjoint",stop_after_dataflow)]
+fn foo(x: Pair, y: Pair, u: U, a: [S; 2], i: usize, j: usize) {
+    unsafe {
+        // Different fields of a struct.
+        rustc_peek(&x.a);
+        rustc_peek(&x.b); //~ ERROR rustc_peek: disjoint
+
+        // Different locals.
+        rustc_peek(&x);
+        rustc_peek(&y); //~ ERROR rustc_peek: disjoint
+
+        // The same local.
+        rustc_peek(&x);
+        rustc_peek(&x); //~ ERROR rustc_peek: not disjoint
+
+        // A struct and one of its fields.
+        rustc_peek(&x);
+        rustc_peek(&x.a); //~ ERROR rustc_peek: not disjoint
+
+        // Different fields of a union share their memory.
+        rustc_peek(&u.a);
+        rustc_peek(&u.b); //~ ERROR rustc_peek: not disjoint
+
+        // The indices may be equal.
+        rustc_peek(&a[i]);
+        rustc_peek(&a[j]); //~ ERROR rustc_peek: not disjoint
+    }
+}
+
+fn main() {
+    let pair = || Pair { a: S(1), b: S(2) };
+    foo(pair(), pair(), U { a: 3 }, [S(4), S(5)], 0, 1);
+}
@@ -0,0 +1,50 @@
+// ignore-tidy-linelength
+
+// Test of `places_conflict::explain_borrow_conflict_with_place`. Consecutive
+// `rustc_peek` calls are paired up, and the second call of each pair reports
+// which step decides whether a borrow of the first argument conflicts with an
+// access to the second.
+
+#![feature(core_intrinsics, rustc_attrs)]
+
+use std::intrinsics::rustc_peek;
+
+struct S(i32);
+struct Pair { a: S, b: S }
+union U { x: u32, y: f32 }
+
+#[rustc_mir(rustc_peek_helper="explain_conflict",stop_after_dataflow)]
+fn foo(s: Pair, t: Pair, u: U, m: &mut S) {
+    unsafe {
+        // Different fields of the same struct.
+        rustc_peek(&s.a);
+        rustc_peek(&s.b); //~ ERROR rustc_peek: DisjointAt(1), shallow access DisjointAt(1), shallow borrow DisjointAt(1)
+
+        // Different locals.
+        rustc_peek(&s);
+        rustc_peek(&t); //~ ERROR rustc_peek: DisjointAt(0), shallow access DisjointAt(0), shallow borrow DisjointAt(0)
+
+        // The same place.
+        rustc_peek(&s.a);
+        rustc_peek(&s.a); //~ ERROR rustc_peek: ConflictEqual, shallow access ConflictEqual, shallow borrow ConflictEqual
+
+        // A borrow of a prefix of the access, and the other way around.
+        rustc_peek(&s);
+        rustc_peek(&s.a); //~ ERROR rustc_peek: ConflictPrefix, shallow access ConflictPrefix, shallow borrow DisjointShallowBorrow
+        rustc_peek(&s.a);
+        rustc_peek(&s); //~ ERROR rustc_peek: ConflictPrefix, shallow access ConflictPrefix, shallow borrow ConflictPrefix
+
+        // A borrow behind a reference that is accessed itself.
+        rustc_peek(&*m);
+        rustc_peek(&m); //~ ERROR rustc_peek: ConflictPrefix, shallow access DisjointPastAccess, shallow borrow ConflictPrefix
+
+        // Different fields of the same union.
+        rustc_peek(&u.x);
+        rustc_peek(&u.y); //~ ERROR rustc_peek: ConflictUnionArbitrary, shallow access ConflictUnionArbitrary, shallow borrow ConflictUnionArbitrary
+    }
+}
+
+fn main() {
+    let mut m = S(5);
+    foo(Pair { a: S(1), b: S(2) }, Pair { a: S(3), b: S(4) }, U { x: 0 }, &mut m);
+}
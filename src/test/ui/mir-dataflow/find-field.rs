@@ -0,0 +1,30 @@
+// Test of `MovePathLookup::find_field`, which returns the move path of a field
+// of a local, if that field is tracked separately.
+
+#![feature(core_intrinsics, rustc_attrs)]
+
+use std::intrinsics::rustc_peek;
+use std::mem::drop;
+
+struct S(i32);
+struct Pair(S, S);
+
+#[rustc_mir(rustc_peek_helper="find_field",stop_after_dataflow)]
+fn foo(p: Pair, q: Pair, t: (S, S)) {
+    // Moving out of both fields of `p` makes each of them tracked.
+    drop(p.0);
+    drop(p.1);
+    // Only the first field of `t` is moved out of.
+    drop(t.0);
+
+    unsafe { rustc_peek(&p.0); } //~ ERROR rustc_peek: move path (_1.0: S)
+    unsafe { rustc_peek(&p.1); } //~ ERROR rustc_peek: move path (_1.1: S)
+    unsafe { rustc_peek(&q.0); } //~ ERROR rustc_peek: field untracked
+    unsafe { rustc_peek(&t.0); } //~ ERROR rustc_peek: move path (_3.0: S)
+    unsafe { rustc_peek(&t.1); } //~ ERROR rustc_peek: field untracked
+    unsafe { rustc_peek(&p); } //~ ERROR rustc_peek: argument must be a field of a local
+}
+
+fn main() {
+    foo(Pair(S(1), S(2)), Pair(S(3), S(4)), (S(5), S(6)));
+}
@@ -0,0 +1,29 @@
+// Test of `MoveData::gather_moves_in`, which only gathers the moves and inits
+// of some of the blocks. For each call to `rustc_peek`, the move data is
+// gathered for the block of the call alone, and the call reports whether its
+// argument is moved out of in that block. Any difference from the move data
+// of the whole body within that block would be reported as well.
+
+#![feature(core_intrinsics, rustc_attrs)]
+
+use std::intrinsics::rustc_peek;
+use std::mem::drop;
+
+struct S(i32);
+struct Pair { a: S, b: S }
+
+#[rustc_mir(rustc_peek_helper="gather_moves_in",stop_after_dataflow)]
+fn foo(p: Pair, q: Pair) {
+    // Moved out of in the block of the call.
+    let _a = p.a;
+    unsafe { rustc_peek(&p.a); } //~ ERROR rustc_peek: moved in block
+    unsafe { rustc_peek(&p.b); } //~ ERROR rustc_peek: not moved in block
+
+    // Moved out of in the block ending with the call to `drop`.
+    drop(q.a);
+    unsafe { rustc_peek(&q.a); } //~ ERROR rustc_peek: not moved in block
+}
+
+fn main() {
+    foo(Pair { a: S(1), b: S(2) }, Pair { a: S(3), b: S(4) });
+}
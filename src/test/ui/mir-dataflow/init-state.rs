@@ -0,0 +1,38 @@
+// Test of `MoveData::init_state_after`. Each `rustc_peek` reports the state
+// of its argument after the last statement preceding the peek.
+
+#![feature(core_intrinsics, rustc_attrs)]
+
+use std::intrinsics::rustc_peek;
+use std::mem::drop;
+
+struct S(i32);
+
+#[rustc_mir(rustc_peek_helper="init_state",stop_after_dataflow)]
+fn foo(test: bool, x: S, y: S) -> S {
+    // All function formal parameters start off initialized.
+    unsafe { rustc_peek(&x); } //~ ERROR rustc_peek: DefinitelyInit
+
+    let ret;
+    unsafe { rustc_peek(&ret); } //~ ERROR rustc_peek: DefinitelyUninit
+
+    // `x` is moved out of by the statement preceding the peek.
+    let z = x;
+    unsafe { rustc_peek(&x); } //~ ERROR rustc_peek: DefinitelyUninit
+
+    // `ret` is initialized by the statement preceding the peek.
+    ret = z;
+    unsafe { rustc_peek(&ret); } //~ ERROR rustc_peek: DefinitelyInit
+
+    // `y` is only moved out of on one path.
+    if test {
+        drop(y);
+    }
+    unsafe { rustc_peek(&y); } //~ ERROR rustc_peek: MaybeInit
+
+    ret
+}
+
+fn main() {
+    foo(true, S(13), S(14));
+}
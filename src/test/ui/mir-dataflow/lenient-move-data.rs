@@ -0,0 +1,22 @@
+// The dataflow sanity check gathers moves with `MoveData::gather_moves_lenient`,
+// so an illegal move is collected as an error, and the move data of the rest
+// of the function can still be used by the dataflow analyses.
+
+#![feature(core_intrinsics, rustc_attrs)]
+
+use std::intrinsics::rustc_peek;
+
+struct S(i32);
+
+#[rustc_mir(rustc_peek_maybe_init,rustc_peek_helper="move_errors",stop_after_dataflow)]
+fn foo(r: &S, x: S) {
+    let _r = *r; //~ ERROR cannot move out of `*r` because it is behind a shared reference
+
+    unsafe { rustc_peek(&x); }
+    let _x = x;
+    unsafe { rustc_peek(&x); } //~ ERROR rustc_peek: bit not set
+}
+
+fn main() {
+    foo(&S(1), S(2));
+}
@@ -0,0 +1,26 @@
+// ignore-tidy-linelength
+
+// Test of `MoveError::display`, which renders each illegal move found while
+// gathering the moves of the function.
+
+#![feature(rustc_attrs)]
+
+struct S(i32);
+struct D { s: S }
+impl Drop for D { fn drop(&mut self) {} }
+
+static G: S = S(0);
+
+#[rustc_mir(rustc_peek_helper="move_errors",stop_after_dataflow)]
+fn foo(r: &S, m: &mut S, p: *const S, d: D, a: [S; 2]) {
+    let _g = G; //~ ERROR cannot move out of static item
+    let _r = *r; //~ ERROR cannot move out of `*r` because it is behind a shared reference
+    let _m = *m; //~ ERROR cannot move out of `*m` because it is behind a mutable reference
+    let _p = unsafe { *p }; //~ ERROR cannot move out of `*p` because it is behind a raw pointer
+    let _d = d.s; //~ ERROR cannot move out of type `D`, which implements the `Drop` trait
+    let _a = a[0]; //~ ERROR cannot move out of type `[S; 2]`, a non-copy array
+}
+
+fn main() {
+    foo(&S(1), &mut S(2), &S(3), D { s: S(4) }, [S(5), S(6)]);
+}
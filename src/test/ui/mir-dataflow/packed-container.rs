@@ -0,0 +1,37 @@
+// Test of `util::packed_container_of`. Each `rustc_peek` call reports the
+// innermost packed struct that its argument is a possibly disaligned field of.
+
+#![feature(core_intrinsics, rustc_attrs)]
+
+use std::intrinsics::rustc_peek;
+
+struct Aligned { x: u32 }
+
+#[repr(packed)]
+struct Inner { a: u8, mid: Aligned, r: &'static Aligned }
+
+#[repr(packed)]
+struct Outer { b: u32, inner: Inner }
+
+#[rustc_mir(rustc_peek_helper="packed_container",stop_after_dataflow)]
+fn foo(outer: Outer, aligned: Aligned) {
+    unsafe {
+        rustc_peek(&outer.b); //~ ERROR rustc_peek: packed container Outer
+        rustc_peek(&outer.inner.mid); //~ ERROR rustc_peek: packed container Inner
+        rustc_peek(&outer.inner.mid.x); //~ ERROR rustc_peek: packed container Inner
+
+        // Alignment 1 cannot be violated.
+        rustc_peek(&outer.inner); //~ ERROR rustc_peek: not disaligned
+        rustc_peek(&outer.inner.a); //~ ERROR rustc_peek: not disaligned
+
+        // The referent of a packed field is aligned.
+        rustc_peek(&outer.inner.r.x); //~ ERROR rustc_peek: not disaligned
+
+        rustc_peek(&aligned.x); //~ ERROR rustc_peek: not disaligned
+    }
+}
+
+fn main() {
+    static A: Aligned = Aligned { x: 1 };
+    foo(Outer { b: 2, inner: Inner { a: 3, mid: Aligned { x: 4 }, r: &A } }, Aligned { x: 5 });
+}
@@ -0,0 +1,33 @@
+// ignore-tidy-linelength
+
+// Test of `MovePathLookup::prefixes`, which returns the tracked move paths
+// from the root local down to the deepest tracked prefix of a place.
+
+#![feature(core_intrinsics, rustc_attrs)]
+
+use std::intrinsics::rustc_peek;
+use std::mem::drop;
+
+struct S(i32);
+struct Inner { a: S, b: S }
+struct Outer { inner: Inner }
+
+static G: S = S(0);
+
+#[rustc_mir(rustc_peek_helper="prefixes",stop_after_dataflow)]
+fn foo(o: Outer) {
+    // Moving out of `o.inner.a` makes `o.inner` and `o.inner.a` tracked.
+    drop(o.inner.a);
+
+    unsafe { rustc_peek(&o); } //~ ERROR rustc_peek: prefixes [_1]
+    unsafe { rustc_peek(&o.inner.a); } //~ ERROR rustc_peek: prefixes [_1, (_1.0: Inner), ((_1.0: Inner).0: S)]
+
+    // `o.inner.b` is not tracked, so this stops at `o.inner`.
+    unsafe { rustc_peek(&o.inner.b); } //~ ERROR rustc_peek: prefixes [_1, (_1.0: Inner)]
+
+    unsafe { rustc_peek(&G); } //~ ERROR rustc_peek: prefixes []
+}
+
+fn main() {
+    foo(Outer { inner: Inner { a: S(1), b: S(2) } });
+}
@@ -0,0 +1,39 @@
+// Test of `places_conflict::is_reborrow_of`. Consecutive `rustc_peek` calls
+// are paired up, and the second call of each pair reports whether an access to
+// its argument reborrows the referent of the first argument.
+
+#![feature(core_intrinsics, rustc_attrs)]
+
+use std::intrinsics::rustc_peek;
+
+struct S(i32);
+struct Pair { a: S, b: S }
+
+#[rustc_mir(rustc_peek_helper="reborrow",stop_after_dataflow)]
+fn foo(x: &Pair, y: (&S, S)) {
+    unsafe {
+        // The referent of `x`, or a field of it.
+        rustc_peek(&x);
+        rustc_peek(&*x); //~ ERROR rustc_peek: reborrow
+        rustc_peek(&x);
+        rustc_peek(&x.a); //~ ERROR rustc_peek: reborrow
+
+        // `x` itself.
+        rustc_peek(&x);
+        rustc_peek(&x); //~ ERROR rustc_peek: not a reborrow
+
+        // The other way around.
+        rustc_peek(&*x);
+        rustc_peek(&x); //~ ERROR rustc_peek: not a reborrow
+
+        // A field of `y` that is not behind the reference.
+        rustc_peek(&y.0);
+        rustc_peek(&y.1); //~ ERROR rustc_peek: not a reborrow
+        rustc_peek(&y.0);
+        rustc_peek(&*y.0); //~ ERROR rustc_peek: reborrow
+    }
+}
+
+fn main() {
+    foo(&Pair { a: S(1), b: S(2) }, (&S(3), S(4)));
+}
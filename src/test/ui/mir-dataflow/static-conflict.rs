@@ -0,0 +1,52 @@
+// Test of the check for places rooted at the same static in
+// `places_conflict`. Consecutive `rustc_peek` calls are paired up, and the
+// second call of each pair reports the outcome of the check for a borrow of
+// the first argument and an access to the second, along with the outcome of
+// the general walk it short-circuits.
+
+#![feature(core_intrinsics, rustc_attrs)]
+
+use std::intrinsics::rustc_peek;
+
+struct S(i32);
+struct Pair { a: S, b: S }
+
+static G: Pair = Pair { a: S(1), b: S(2) };
+static H: Pair = Pair { a: S(3), b: S(4) };
+static A: [S; 2] = [S(5), S(6)];
+static mut M: Pair = Pair { a: S(7), b: S(8) };
+
+#[rustc_mir(rustc_peek_helper="static_conflict",stop_after_dataflow)]
+fn foo(i: usize) {
+    unsafe {
+        // The same static through the same projections.
+        rustc_peek(&G);
+        rustc_peek(&G); //~ ERROR rustc_peek: fast path Some(ConflictEqual), walk ConflictEqual
+        rustc_peek(&G.a);
+        rustc_peek(&G.a); //~ ERROR rustc_peek: fast path Some(ConflictEqual), walk ConflictEqual
+
+        // Different fields of the same static.
+        rustc_peek(&G.a);
+        rustc_peek(&G.b); //~ ERROR rustc_peek: fast path None, walk DisjointAt(1)
+
+        // A static and a field of it.
+        rustc_peek(&G);
+        rustc_peek(&G.a); //~ ERROR rustc_peek: fast path None, walk ConflictPrefix
+
+        // Different statics.
+        rustc_peek(&G);
+        rustc_peek(&H); //~ ERROR rustc_peek: fast path None, walk DisjointAt(0)
+
+        // Mutable statics are ignored.
+        rustc_peek(&M.a);
+        rustc_peek(&M.a); //~ ERROR rustc_peek: fast path None, walk DisjointAt(0)
+
+        // Whether indexing conflicts depends on the bias, so it is left to the walk.
+        rustc_peek(&A[i]);
+        rustc_peek(&A[i]); //~ ERROR rustc_peek: fast path None, walk ConflictEqual
+    }
+}
+
+fn main() {
+    foo(0);
+}
@@ -0,0 +1,26 @@
+// ignore-tidy-linelength
+
+// Test of `places_conflict::strip_downcast`, which removes the downcast to an
+// enum variant from the end of a place. It is reported for each borrow that
+// goes through a downcast, as created by a `ref` binding in a pattern.
+
+#![feature(core_intrinsics, rustc_attrs)]
+
+struct S(i32);
+struct Pair { a: S, b: S }
+
+#[rustc_mir(rustc_peek_helper="strip_downcast",stop_after_dataflow)]
+fn foo(x: Result<S, S>, y: Option<Pair>) {
+    // A field of a variant.
+    if let Ok(ref _v) = x {} //~ ERROR rustc_peek: strip_downcast(((_1 as Ok).0: S)) = _1
+
+    // The downcast is not at the end of the place, so it is kept.
+    if let Some(Pair { a: ref _a, .. }) = y {} //~ ERROR rustc_peek: strip_downcast((((_2 as Some).0: Pair).0: S)) = (((_2 as Some).0: Pair).0: S)
+
+    // Places without a downcast are not reported.
+    let _r = &x;
+}
+
+fn main() {
+    foo(Ok(S(1)), Some(Pair { a: S(2), b: S(3) }));
+}
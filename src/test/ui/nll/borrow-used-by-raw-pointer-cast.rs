@@ -0,0 +1,9 @@
+// Check that a borrow whose only later use is being cast to a raw pointer
+// is still explained as being used there.
+
+fn main() {
+    let mut x = 0;
+    let r = &mut x;
+    x = 1; //~ ERROR cannot assign to `x` because it is borrowed
+    let _p = r as *mut i32;
+}
@@ -0,0 +1,19 @@
+// Test of the search for the point where the region of a borrow becomes live,
+// for a two-phase borrow: the use that keeps the mutable borrow of `v` live is
+// the call to `push`, which activates it.
+
+// compile-flags: -Z borrowck=mir
+
+#![feature(rustc_attrs)]
+
+#[rustc_mir(borrowck_live_start)]
+fn foo() {
+    let mut v = Vec::new();
+    v.push(v.len());
+    //~^ ERROR rustc_mir: borrow becomes live here
+    //~| ERROR rustc_mir: borrow becomes live here
+}
+
+fn main() {
+    foo();
+}
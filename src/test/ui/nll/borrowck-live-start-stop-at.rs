@@ -0,0 +1,22 @@
+// Test of the search for the point where the region of a borrow becomes live,
+// bounded at the call to `stop`: the use of `r` after that call is not found.
+
+// compile-flags: -Z borrowck=mir
+
+#![feature(rustc_attrs)]
+
+#[rustc_mir(borrowck_live_start, stop_at_call="stop")]
+fn foo() {
+    let x = 0;
+    let r;
+    r = &x;
+    let y = 1; //~ ERROR rustc_mir: borrow becomes live here
+    stop(y);
+    drop(r);
+}
+
+fn stop(_: i32) {}
+
+fn main() {
+    foo();
+}
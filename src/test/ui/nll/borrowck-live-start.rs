@@ -0,0 +1,21 @@
+// Test of the search for the point where the region of a borrow becomes live.
+// The search starts at the beginning of the body, and goes through the points
+// of the `if` where the region is not live yet.
+
+// compile-flags: -Z borrowck=mir
+
+#![feature(rustc_attrs)]
+
+#[rustc_mir(borrowck_live_start)]
+fn foo(c: bool) {
+    let mut x = 0;
+    if c {
+        x = 1;
+    }
+    let r = &x; //~ ERROR rustc_mir: borrow becomes live here
+    drop(r);
+}
+
+fn main() {
+    foo(true);
+}
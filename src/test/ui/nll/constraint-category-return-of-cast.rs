@@ -0,0 +1,15 @@
+// Check that when a constraint arises both from a cast and from returning
+// the cast value, NLL blames the return.
+
+// compile-flags: -Z borrowck=mir
+
+#![allow(dead_code)]
+
+use std::fmt::Debug;
+
+fn bar<'a>(x: &'a u32) -> &'static dyn Debug {
+    x as &dyn Debug
+    //~^ ERROR lifetime may not live long enough
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+// Test that when a region error arises from computing the common type of
+// the two branches of an `if`, the error says so.
+
+fn pick<'a, 'b>(c: bool, x: Option<&'a u32>, y: Option<&'b u32>) -> Option<&'a u32> {
+    let r = if c { x } else { y }; //~ ERROR cannot infer an appropriate lifetime
+    r
+}
+
+fn main() {}
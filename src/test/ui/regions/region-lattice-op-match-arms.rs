@@ -0,0 +1,12 @@
+// Test that when a region error arises from computing the common type of
+// two match arms, the error says so.
+
+fn pick<'a, 'b>(c: bool, x: Option<&'a u32>, y: Option<&'b u32>) -> Option<&'a u32> {
+    let r = match c {
+        true => x,
+        false => y, //~ ERROR cannot infer an appropriate lifetime
+    };
+    r
+}
+
+fn main() {}
@@ -0,0 +1,27 @@
+// Check that conflicting borrows through different fields of a union are
+// reported against the union they diverge at, even when one of the places
+// goes on through a nested union.
+
+#![allow(unused)]
+
+#[derive(Clone, Copy)]
+union Inner {
+    x: u32,
+    y: u32,
+}
+
+union Outer {
+    a: Inner,
+    b: u32,
+}
+
+fn use_borrow<T>(_: &T) {}
+
+unsafe fn nested_union_borrow() {
+    let mut u = Outer { b: 0 };
+    let a = &u.b;
+    let b = &mut u.a.x; //~ ERROR cannot borrow `u` (via `u.a.x`)
+    use_borrow(a);
+}
+
+fn main() {}